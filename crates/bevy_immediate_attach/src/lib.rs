@@ -11,10 +11,13 @@ use bevy_ecs::{
     lifecycle,
     observer::On,
     query::With,
+    schedule::{InternedScheduleLabel, IntoScheduleConfigs, ScheduleLabel},
     system::{Commands, In, Query, StaticSystemParam, SystemParam},
 };
 
-use bevy_immediate_core::{BevyImmediatePlugin, CapSet, Imm, ImmCtx, ImmId};
+use bevy_immediate_core::{
+    BevyImmediatePlugin, CapSet, Imm, ImmCtx, ImmId, ImmSchedules, ImmediateSystemSet,
+};
 
 /// Implement trait to be able to attach immediate tree in arbitrary place
 ///
@@ -35,11 +38,13 @@ pub trait ImmediateAttach<Caps: CapSet>: Component {
 
 /// Add plugin to execute immediate tree so that it is attached to an existing entity
 ///
-/// Immediate tree will be refreshed during each bevy_app::Update schedule or
+/// Immediate tree will be refreshed during each build schedule ([`bevy_app::Update`] by default) or
 /// after `RootComponent` is added to entity to avoid 1 frame delay.
 ///
 /// For `RootComponent` trait [`ImmediateAttachRoot`] must be implemented.
 pub struct BevyImmediateAttachPlugin<Caps: CapSet, RootComponent: ImmediateAttach<Caps>> {
+    build_schedule: Option<InternedScheduleLabel>,
+    upkeep_schedule: Option<InternedScheduleLabel>,
     _ph: PhantomData<(Caps, RootComponent)>,
 }
 
@@ -48,7 +53,28 @@ impl<Caps: CapSet, RootComponent: ImmediateAttach<Caps>>
 {
     /// Construct plugin
     pub fn new() -> Self {
-        Self { _ph: PhantomData }
+        Self {
+            build_schedule: None,
+            upkeep_schedule: None,
+            _ph: PhantomData,
+        }
+    }
+
+    /// Schedule in which immediate tree is built
+    ///
+    /// Defaults to [`ImmSchedules::build`] of capability set.
+    pub fn with_build_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.build_schedule = Some(schedule.intern());
+        self
+    }
+
+    /// Schedule in which upkeep systems are executed.
+    /// See [`BevyImmediatePlugin::with_upkeep_schedule`]
+    ///
+    /// Applied only when this plugin adds [`BevyImmediatePlugin`].
+    pub fn with_upkeep_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.upkeep_schedule = Some(schedule.intern());
+        self
     }
 }
 
@@ -65,12 +91,31 @@ impl<Caps: CapSet, RootComponent: ImmediateAttach<Caps>> bevy_app::Plugin
 {
     fn build(&self, app: &mut bevy_app::App) {
         if !app.is_plugin_added::<BevyImmediatePlugin<Caps>>() {
-            app.add_plugins(BevyImmediatePlugin::<Caps>::new());
+            let mut plugin = BevyImmediatePlugin::<Caps>::new();
+            if let Some(schedule) = self.build_schedule {
+                plugin = plugin.with_build_schedule(schedule);
+            }
+            if let Some(schedule) = self.upkeep_schedule {
+                plugin = plugin.with_upkeep_schedule(schedule);
+            }
+            app.add_plugins(plugin);
+        } else if let Some(schedule) = self.upkeep_schedule
+            && app.world().resource::<ImmSchedules<Caps>>().upkeep() != schedule
+        {
+            log::warn!(
+                "BevyImmediatePlugin was already added with different upkeep schedule, {:?} is ignored",
+                schedule
+            );
         }
 
+        let build_schedule = self
+            .build_schedule
+            .unwrap_or_else(|| app.world().resource::<ImmSchedules<Caps>>().build());
+
         app.add_systems(
-            bevy_app::Update,
-            run_system_each_frame::<Caps, RootComponent>,
+            build_schedule,
+            run_system_each_frame::<Caps, RootComponent>
+                .before(ImmediateSystemSet::<Caps>::default()),
         );
         app.add_observer(on_insert::<Caps, RootComponent>);
     }
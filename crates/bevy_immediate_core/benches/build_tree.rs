@@ -0,0 +1,96 @@
+//! Measures cost of building large immediate mode entity trees
+//!
+//! Run with `cargo bench -p bevy_immediate_core`
+
+use std::time::{Duration, Instant};
+
+use bevy_immediate_core::{
+    CapSet, Imm, ImmCapAccessRequests, ImmCapability, ImmEntity, ImplCapsEmpty,
+    impl_capability_set, testing::ImmTestApp,
+};
+
+/// Capability that uses temporary entity store like most UI capabilities do
+struct CapabilityBenchTmpStore;
+
+impl ImmCapability for CapabilityBenchTmpStore {
+    fn build<Caps: CapSet>(_app: &mut bevy_app::App, cap_req: &mut ImmCapAccessRequests<Caps>) {
+        cap_req.add_on_children_event_listener(Box::new(store_depth::<Caps>));
+    }
+}
+
+fn store_depth<Caps: CapSet>(entity: &mut ImmEntity<Caps>) {
+    let id = entity.imm_id().raw();
+    entity.cap_entity_tmp_store_mut().insert(id);
+}
+
+struct CapsBench;
+impl_capability_set!(
+    CapsBench,
+    ImplCapsBench > ImplCapsEmpty,
+    (CapabilityBenchTmpStore)
+);
+
+struct CapsBenchEmpty;
+impl_capability_set!(CapsBenchEmpty, ImplCapsBenchEmpty > ImplCapsEmpty, ());
+
+const ROWS: usize = 200;
+const COLUMNS: usize = 100;
+const DETACHED_ROOTS: usize = 5000;
+const FRAMES: usize = 50;
+
+fn bench<Caps: CapSet>(name: &str, nodes: usize, build: impl Fn(&mut Imm<Caps>)) {
+    let mut app = ImmTestApp::<Caps>::new();
+
+    let run = |app: &mut ImmTestApp<Caps>| {
+        let mut build_time = Duration::ZERO;
+        app.run_frame(|ui| {
+            // System parameters are already fetched
+            let start = Instant::now();
+            build(ui);
+            build_time = start.elapsed();
+        });
+        build_time
+    };
+
+    // Spawn entities
+    run(&mut app);
+
+    let total: Duration = (0..FRAMES).map(|_| run(&mut app)).sum();
+    let nodes = (nodes * FRAMES) as f64;
+    println!(
+        "{name:<32} {:>8.1} ns/node {:>8.2} ms/frame",
+        total.as_nanos() as f64 / nodes,
+        total.as_secs_f64() * 1000.0 / FRAMES as f64,
+    );
+}
+
+fn build_grid<Caps: CapSet>(ui: &mut Imm<Caps>) {
+    for row in 0..ROWS {
+        ui.ch_id(row).add(|ui| {
+            for column in 0..COLUMNS {
+                let entity = ui.ch_id(column);
+                let _ = entity.cap_entity_tmp_store().get::<u64>();
+            }
+        });
+    }
+}
+
+/// Every entity is recorded as detached root of the same owner
+fn build_detached_roots<Caps: CapSet>(ui: &mut Imm<Caps>) {
+    ui.unrooted("roots", |ui| {
+        for idx in 0..DETACHED_ROOTS {
+            ui.ch_id(idx);
+        }
+    });
+}
+
+fn main() {
+    let grid_nodes = ROWS * (COLUMNS + 1);
+    bench::<CapsBenchEmpty>("build tree (no capabilities)", grid_nodes, build_grid);
+    bench::<CapsBench>("build tree (tmp store capability)", grid_nodes, build_grid);
+    bench::<CapsBenchEmpty>(
+        "build unrooted tree (many roots)",
+        DETACHED_ROOTS,
+        build_detached_roots,
+    );
+}
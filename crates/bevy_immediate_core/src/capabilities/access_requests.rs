@@ -3,6 +3,7 @@ use std::sync::Arc;
 use bevy_ecs::{
     component::{Component, ComponentId, Mutable},
     resource::Resource,
+    system::EntityCommands,
     world::World,
 };
 use bevy_platform::collections::HashMap;
@@ -32,6 +33,9 @@ pub struct ImmCapAccessRequests<Caps: CapSet> {
 
     #[allow(clippy::type_complexity)]
     pub(crate) on_children: Vec<Box<dyn Fn(&mut ImmEntity<'_, '_, '_, Caps>) + Send + Sync>>,
+
+    #[allow(clippy::type_complexity)]
+    pub(crate) on_pool_reset: Vec<Box<dyn Fn(&mut EntityCommands) + Send + Sync>>,
 }
 
 impl<Caps: CapSet> Default for ImmCapAccessRequests<Caps> {
@@ -40,6 +44,7 @@ impl<Caps: CapSet> Default for ImmCapAccessRequests<Caps> {
             components: Default::default(),
             resources: Default::default(),
             on_children: Default::default(),
+            on_pool_reset: Default::default(),
         }
     }
 }
@@ -77,6 +82,17 @@ impl<Caps: CapSet> ImmCapAccessRequests<Caps> {
         self.on_children.push(listener);
     }
 
+    /// Add listener that will be called when entity is moved into [`crate::ImmEntityPool`]
+    ///
+    /// Listener should reset entity state that should not leak into reused entity.
+    #[allow(clippy::type_complexity)]
+    pub fn add_on_pool_reset_listener(
+        &mut self,
+        listener: Box<dyn Fn(&mut EntityCommands) + Send + Sync>,
+    ) {
+        self.on_pool_reset.push(listener);
+    }
+
     fn request_component_inner<C: Component>(&mut self, world: &mut World, mutable: bool) {
         let component_id = world.register_component::<C>();
         // self.type_id_map.insert(TypeId::of::<C>(), component_id);
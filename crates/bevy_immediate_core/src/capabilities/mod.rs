@@ -28,11 +28,38 @@ pub trait ImplCap<T>: CapSet {}
 /// Defines trait `ImplCapMy` that can be used to easily check that CapSet has
 /// at least all capabilities in `CapMy`.
 ///
-/// Additionally at compile time checks that `ImplChildSet` is subset of `CapMy` capabilities.
+/// Additionally at compile time checks that `ImplChildSet` is subset of `CapMy` capabilities
+/// and that requirements declared with [`ImmCapabilityRequires`] are part of the set.
+///
+/// Capabilities can be provided by list macro defined with [`crate::capability_list`],
+/// optionally extended with additional capabilities:
+///
+/// ```ignore
+/// pub struct CapsMyUi;
+///
+/// bevy_immediate::impl_capability_set!(
+///     CapsMyUi,
+///     ImplCapsMyUi > ImplCapsUiFeathers,
+///     bevy_immediate::ui::caps_ui_feathers! + (CapabilityMy1, CapabilityMy2)
+/// );
+/// ```
 ///
 /// See examples for correct use!
 #[macro_export]
 macro_rules! impl_capability_set {
+    (@list $name:ty, $set_trait:ident > $subset_check:ident, ($($t:ty),*) [$($base:ty),*]) => {
+        $crate::impl_capability_set!($name, $set_trait > $subset_check, ($($base,)* $($t),*));
+    };
+    (
+        $name:ty,
+        $set_trait:ident > $subset_check:ident,
+        $($list:ident)::+ ! $(+ ($($t:ty),* $(,)?))?
+    ) => {
+        $($list)::+ ! {
+            [$crate::impl_capability_set]
+            @list $name, $set_trait > $subset_check, ($($($t),*)?)
+        }
+    };
     ($name:ty, $set_trait:ident > $subset_check:ident, ($($t:ty),* $(,)?)) => {
         impl $crate::CapSet for $name {
             fn initialize<Caps: $crate::CapSet>(
@@ -61,6 +88,18 @@ macro_rules! impl_capability_set {
             fn [< _check_ $set_trait _implements_at_least_provided_subset $subset_check >]() {
                 [<_subset_check_for $set_trait>]::<$name>();
             }
+
+            #[allow(warnings)]
+            fn [< _check_ $set_trait _implements_required_capabilities >]() {
+                use $crate::capabilities::requirements_check::{
+                    DeclaredRequirements, NoRequirements, RequirementsCheck, assert_requirements,
+                };
+                $(
+                    assert_requirements::<$name, _>(
+                        (&RequirementsCheck::<$t, $name>::new()).requirements(),
+                    );
+                )*
+            }
         }
     };
 }
@@ -83,6 +122,12 @@ impl ImmCapability for () {
     fn build<Caps: CapSet>(_app: &mut bevy_app::App, _cap_req: &mut ImmCapAccessRequests<Caps>) {}
 }
 
+/// Implements compile time checks for capabilities required by other capabilities
+mod requirements;
+#[doc(hidden)]
+pub use requirements::check as requirements_check;
+pub use requirements::{ImmCapabilityRequires, ImplCapAll};
+
 /// Implements logic for collecting requested components and resources
 mod access_requests;
 pub use access_requests::{ImmCapAccessRequests, ImmCapAccessRequestsResource};
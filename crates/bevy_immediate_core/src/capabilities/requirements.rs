@@ -0,0 +1,84 @@
+use crate::{CapSet, ImplCap};
+
+/// Declares capabilities that must be present in capability set together with this capability
+///
+/// Requirements are checked at compile time by [`crate::impl_capability_set`].
+///
+/// ```ignore
+/// impl ImmCapabilityRequires for CapabilityUiAnchored {
+///     type Capabilities = (CapabilityUiInteraction,);
+/// }
+/// ```
+pub trait ImmCapabilityRequires {
+    /// Tuple of required capabilities
+    type Capabilities;
+}
+
+/// Implemented for tuples of capabilities that are all implemented by capability set `Caps`
+pub trait ImplCapAll<Caps: CapSet> {}
+
+impl<Caps: CapSet> ImplCapAll<Caps> for () {}
+
+macro_rules! impl_cap_all {
+    ($($t:ident),*) => {
+        impl<Caps: CapSet $(+ ImplCap<$t>)*, $($t),*> ImplCapAll<Caps> for ($($t,)*) {}
+    };
+}
+
+impl_cap_all!(C1);
+impl_cap_all!(C1, C2);
+impl_cap_all!(C1, C2, C3);
+impl_cap_all!(C1, C2, C3, C4);
+impl_cap_all!(C1, C2, C3, C4, C5);
+impl_cap_all!(C1, C2, C3, C4, C5, C6);
+impl_cap_all!(C1, C2, C3, C4, C5, C6, C7);
+impl_cap_all!(C1, C2, C3, C4, C5, C6, C7, C8);
+
+/// Used by [`crate::impl_capability_set`] to check requirements of capabilities
+/// that implement [`ImmCapabilityRequires`]. Capabilities without declared requirements are accepted.
+#[doc(hidden)]
+pub mod check {
+    use std::marker::PhantomData;
+
+    use super::{ImmCapabilityRequires, ImplCapAll};
+    use crate::CapSet;
+
+    pub struct RequirementsCheck<Cap, Caps>(PhantomData<(Cap, Caps)>);
+
+    impl<Cap, Caps> RequirementsCheck<Cap, Caps> {
+        pub fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    impl<Cap, Caps> Default for RequirementsCheck<Cap, Caps> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Selected by method resolution when capability declares requirements
+    pub trait DeclaredRequirements {
+        type Capabilities;
+
+        fn requirements(&self) -> PhantomData<Self::Capabilities> {
+            PhantomData
+        }
+    }
+
+    impl<Cap: ImmCapabilityRequires, Caps> DeclaredRequirements for RequirementsCheck<Cap, Caps> {
+        type Capabilities = Cap::Capabilities;
+    }
+
+    /// Fallback through autoref when capability does not declare requirements
+    pub trait NoRequirements {
+        fn requirements(&self) -> PhantomData<()> {
+            PhantomData
+        }
+    }
+
+    impl<Cap, Caps> NoRequirements for &RequirementsCheck<Cap, Caps> {}
+
+    pub fn assert_requirements<Caps: CapSet, Required: ImplCapAll<Caps>>(_: PhantomData<Required>) {
+    }
+}
@@ -3,17 +3,19 @@ use std::{any::TypeId, marker::PhantomData};
 use bevy_ecs::{
     entity::Entity,
     resource::Resource,
-    schedule::IntoScheduleConfigs,
+    schedule::{InternedScheduleLabel, IntoScheduleConfigs},
     system::{Query, ResMut},
 };
-use bevy_platform::collections::{HashMap, hash_map::Entry};
+use bevy_platform::collections::{HashMap, HashSet, hash_map::Entry};
 
-use crate::{ImmId, ImmediateSystemSet};
+use crate::{ImmId, ImmediateSystemSet, imm_rebuild_needed};
 
-pub fn init<Caps: Send + Sync + 'static>(app: &mut bevy_app::App) {
+pub fn init<Caps: Send + Sync + 'static>(app: &mut bevy_app::App, schedule: InternedScheduleLabel) {
     app.add_systems(
-        bevy_app::PostUpdate,
-        clean_cached::<Caps>.in_set(ImmediateSystemSet::<Caps>::default()),
+        schedule,
+        clean_cached::<Caps>
+            .in_set(ImmediateSystemSet::<Caps>::default())
+            .run_if(imm_rebuild_needed::<Caps>),
     );
     app.insert_resource(CachedHash::<Caps>::default());
 }
@@ -50,6 +52,20 @@ fn inner_remove(this: &mut HashMap<Key, ImmId>, key: Key) -> Option<ImmId> {
 }
 
 impl<Caps> CachedHash<Caps> {
+    /// Amount of cached values
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Remove all values stored for provided entities
+    pub fn remove_entities(&mut self, entities: &HashSet<Entity>) {
+        if entities.is_empty() {
+            return;
+        }
+        self.values
+            .retain(|key, _value| !entities.contains(&key.entity));
+    }
+
     pub fn set(&mut self, entity: Entity, key: ImmId, value: ImmId) -> bool {
         inner_cache(
             &mut self.values,
@@ -1,8 +1,8 @@
 use bevy_ecs::{
     entity::Entity,
-    hierarchy::ChildOf,
-    query::With,
-    system::{Commands, Query, Res, ResMut, SystemChangeTick},
+    hierarchy::{ChildOf, Children},
+    query::{Has, With},
+    system::{Commands, Local, Query, Res, ResMut, SystemChangeTick},
 };
 
 use crate::{
@@ -10,7 +10,8 @@ use crate::{
     capabilities::ImmCapResourcesParam,
     immediate::{
         Current, CurrentEntity, ImmMarker, cached_hash::CachedHash,
-        entity_mapping::ImmediateModeEntityMapping, upkeep::ImmediateModeStateResource,
+        entity_mapping::ImmediateModeEntityMapping, local_state::LocalState,
+        upkeep::ImmediateModeStateResource,
     },
 };
 
@@ -35,13 +36,36 @@ pub struct ImmCtx<'w, 's, Caps: CapSet> {
     pub system_change_tick: SystemChangeTick,
 
     pub(super) access_requests: Res<'w, ImmCapAccessRequestsResource<Caps>>,
-    pub(super) state: Res<'w, ImmediateModeStateResource<Caps>>,
+    pub(super) state: ResMut<'w, ImmediateModeStateResource<Caps>>,
     pub(super) mapping: Res<'w, ImmediateModeEntityMapping<Caps>>,
     pub(super) entity_query: Query<'w, 's, ImmEntityQuery<Caps>, (With<ImmMarker<Caps>>, ())>,
     pub(super) cached_hash: ResMut<'w, CachedHash<Caps>>,
+    pub(super) local_state: ResMut<'w, LocalState<Caps>>,
+    pub(super) pool: ResMut<'w, super::ImmEntityPool<Caps>>,
+    pub(super) pooled_query: super::pool::ImmPooledQuery<'w, 's>,
+    pub(super) children_query: Query<'w, 's, &'static Children>,
+    pub(super) frame_stats: ResMut<'w, super::ImmFrameStats<Caps>>,
+    pub(super) auto_id_config: Res<'w, super::ImmAutoIdConfig<Caps>>,
+    pub(super) call_sites: Local<'s, super::id::CallSiteOccurrences>,
+    pub(super) tmp_stores: Local<'s, crate::utils::ImmTypeMapStack>,
+    pub(super) detached: ResMut<'w, super::detached::DetachedRoots<Caps>>,
+    pub(super) keep_alive: Local<'s, Vec<Entity>>,
+
+    #[cfg(debug_assertions)]
+    pub(super) id_collisions: ResMut<'w, super::ImmIdCollisions<Caps>>,
+    #[cfg(debug_assertions)]
+    pub(super) debug_labels: Query<'w, 's, &'static super::ImmDebugLabel>,
+    #[cfg(debug_assertions)]
+    pub(super) debug_paths: ResMut<'w, super::debug_path::ImmDebugPaths<Caps>>,
+    #[cfg(debug_assertions)]
+    pub(super) diagnostics: Option<ResMut<'w, super::ImmDiagnostics<Caps>>>,
 
     #[cfg(feature = "hotpatching")]
     pub(super) hotpatching: Res<'w, super::hotpatching::HotpatchingCounter>,
+    #[cfg(feature = "hotpatching")]
+    pub(super) hotpatch_mode: Res<'w, super::hotpatching::ImmHotpatchMode>,
+    #[cfg(feature = "hotpatching")]
+    pub(super) hotpatch_applied: ResMut<'w, super::hotpatching::HotpatchApplied<Caps>>,
 }
 
 impl<'w, 's, Caps> ImmCtx<'w, 's, Caps>
@@ -50,12 +74,22 @@ where
 {
     /// Initialize entity hierarchy managed by immediate mode
     ///
-    /// When `hotpatching` feature is enabled. Will combine id with last time when hotpatching was triggered
-    pub fn build_immediate_root<T: std::hash::Hash>(self, root_id: T) -> Imm<'w, 's, Caps> {
+    /// When `hotpatching` feature is enabled and `ImmHotpatchMode::Respawn` is used.
+    /// Will combine id with last time when hotpatching was triggered
+    pub fn build_immediate_root<T: std::hash::Hash>(mut self, root_id: T) -> Imm<'w, 's, Caps> {
+        #[cfg(debug_assertions)]
+        self.debug_paths.set_source(&root_id);
+
         let id = ImmId::new(root_id);
 
         #[cfg(feature = "hotpatching")]
-        let id = ImmId::new((id, self.hotpatching.hotpatch()));
+        let id = self.hotpatch_root_id(id);
+
+        #[cfg(debug_assertions)]
+        self.debug_paths.root(id);
+
+        self.call_sites.clear();
+        let auto_id_mode = self.auto_id_config.mode;
 
         Imm {
             ctx: self,
@@ -64,22 +98,52 @@ where
                 entity: None,
                 auto_id_idx: 0,
                 id_pref: ImmId::new(49382395483011234u64),
+                root_target: None,
+                auto_id_mode,
+                detached: Some(id),
+                depth: 0,
             },
+            world: None,
         }
     }
 
+    /// Initialize entity hierarchy managed by immediate mode that targets given render target
+    ///
+    /// For UI `target` is camera entity. Top level entities (including unrooted entity trees)
+    /// receive `UiTargetCamera` when `bevy_immediate_ui` capabilities are used.
+    /// Target is available with [`Imm::root_target`].
+    pub fn build_immediate_root_for<T: std::hash::Hash>(
+        self,
+        root_id: T,
+        target: Entity,
+    ) -> Imm<'w, 's, Caps> {
+        let mut imm = self.build_immediate_root(root_id);
+        imm.current.root_target = Some(target);
+        imm
+    }
+
     /// Initialize entity hierarchy managed by immediate mode starting from given **existing** entity
     ///
-    /// When `hotpatching` feature is enabled. Will combine id with last time when hotpatching was triggered
+    /// When `hotpatching` feature is enabled and `ImmHotpatchMode::Respawn` is used.
+    /// Will combine id with last time when hotpatching was triggered
     pub fn build_immediate_from<T: std::hash::Hash>(
-        self,
+        mut self,
         root_id: T,
         entity: Entity,
     ) -> Imm<'w, 's, Caps> {
+        #[cfg(debug_assertions)]
+        self.debug_paths.set_source(&root_id);
+
         let id = ImmId::new(root_id);
 
         #[cfg(feature = "hotpatching")]
-        let id = ImmId::new((id, self.hotpatching.hotpatch()));
+        let id = self.hotpatch_root_id(id);
+
+        #[cfg(debug_assertions)]
+        self.debug_paths.root(id);
+
+        self.call_sites.clear();
+        let auto_id_mode = self.auto_id_config.mode;
 
         Imm {
             ctx: self,
@@ -91,10 +155,51 @@ where
                 }),
                 auto_id_idx: 0,
                 id_pref: ImmId::new(49382395483011234u64),
+                root_target: None,
+                auto_id_mode,
+                detached: Some(id),
+                depth: 0,
             },
+            world: None,
+        }
+    }
+
+    #[cfg(feature = "hotpatching")]
+    fn hotpatch_root_id(&mut self, id: ImmId) -> ImmId {
+        self.hotpatch_applied
+            .build(&self.hotpatching, self.state.iteration);
+
+        match *self.hotpatch_mode {
+            super::ImmHotpatchMode::Respawn => ImmId::new((id, self.hotpatching.hotpatch())),
+            super::ImmHotpatchMode::RerunSpawnHooks => id,
+        }
+    }
+
+    /// `on_spawn_*` functions should be executed again for existing entities
+    #[inline]
+    pub(super) fn rerun_spawn_hooks(&self) -> bool {
+        #[cfg(feature = "hotpatching")]
+        {
+            self.hotpatch_applied
+                .rerun_spawn_hooks(*self.hotpatch_mode, self.state.iteration)
+        }
+        #[cfg(not(feature = "hotpatching"))]
+        {
+            false
         }
     }
 
+    /// Declare that entity tree with given root id is not rebuilt during this frame
+    ///
+    /// Entities built for this root during previous frames are kept intact, while entity trees
+    /// of other roots are maintained as usual. Use [`super::ImmFrameSkip`] to skip
+    /// rebuilding all entity trees.
+    pub fn skip_root<T: std::hash::Hash>(self, root_id: T) {
+        let mut imm = self.build_immediate_root(root_id);
+        let id = imm.current.id;
+        imm.keep_built_alive(None, id);
+    }
+
     /// Access commands
     pub fn commands_mut(&mut self) -> &mut Commands<'w, 's> {
         &mut self.commands
@@ -106,4 +211,6 @@ where
 pub(super) struct ImmEntityQuery<Marker: Send + Sync + 'static> {
     pub(super) tracker: &'static mut ImmMarker<Marker>,
     pub(super) child_of: Option<&'static ChildOf>,
+    pub(super) retained: Has<super::ImmRetained>,
+    pub(super) has_retention: Has<super::ImmRetention>,
 }
@@ -0,0 +1,345 @@
+use std::{fmt::Write as _, hash::Hasher, marker::PhantomData, ops::Range, sync::Arc};
+
+use ahash::HashMap;
+use bevy_ecs::{
+    component::Component,
+    resource::Resource,
+    schedule::{InternedScheduleLabel, IntoScheduleConfigs},
+    system::{Res, ResMut},
+};
+
+use crate::{ImmId, ImmediateSystemSet, immediate::entity_mapping::ImmediateModeEntityMapping};
+
+pub fn init<Caps: Send + Sync + 'static>(app: &mut bevy_app::App, schedule: InternedScheduleLabel) {
+    app.insert_resource(ImmDebugPaths::<Caps>::default());
+    app.add_systems(
+        schedule,
+        prune_debug_paths::<Caps>.in_set(ImmediateSystemSet::<Caps>::default()),
+    );
+}
+
+/// Human readable chain of id sources that produced [`ImmId`] of entity
+///
+/// Added to immediate mode entities only in debug builds (`debug_assertions`).
+///
+/// Path segments are separated by `/`:
+/// * `menu`, `item[3]` - values provided to `.ch_id("menu")`, `.ch_id(("item", 3))`
+/// * `L12:5` - location provided by [`crate::lid`] and [`crate::lch`]
+/// * `#2` - auto generated id (`.ch()`) with its index
+/// * `L12:5#0` - auto generated id with [`crate::ImmAutoIdMode::CallSite`] and its occurrence
+/// * `3:` - prefix added by [`crate::Imm::with_add_id_pref`] and keyed loops
+#[derive(Component, Clone, PartialEq, Eq, Hash)]
+pub struct ImmDebugPath(Arc<str>);
+
+impl ImmDebugPath {
+    /// Path as string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ImmDebugPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::fmt::Debug for ImmDebugPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+/// Source of id segment when value provided by user is not available
+#[derive(Clone, Copy)]
+pub(super) enum IdSource {
+    Auto(usize),
+    CallSite(&'static std::panic::Location<'static>, u32),
+    Manual,
+}
+
+/// Debug paths for capability set `Caps`
+#[derive(Resource)]
+pub(super) struct ImmDebugPaths<Caps> {
+    /// Paths of entity and root ids
+    paths: HashMap<ImmId, Arc<str>>,
+    /// Rendered id prefixes added with `with_add_id_pref`
+    prefixes: HashMap<ImmId, Arc<str>>,
+    /// Value provided by user for next id
+    source: DebugIdWriter,
+    has_source: bool,
+    _ph: PhantomData<Caps>,
+}
+
+impl<Caps> Default for ImmDebugPaths<Caps> {
+    fn default() -> Self {
+        Self {
+            paths: Default::default(),
+            prefixes: Default::default(),
+            source: Default::default(),
+            has_source: false,
+            _ph: PhantomData,
+        }
+    }
+}
+
+impl<Caps> ImmDebugPaths<Caps> {
+    /// Record value that will be used to generate next id
+    pub(super) fn set_source<T: std::hash::Hash>(&mut self, source: &T) {
+        self.source.clear();
+        source.hash(&mut self.source);
+        self.has_source = true;
+    }
+
+    /// Render recorded source into `out`
+    fn write_source(&mut self, out: &mut String, fallback: IdSource, id: ImmId) {
+        if std::mem::take(&mut self.has_source) {
+            self.source.render(out);
+            return;
+        }
+        match fallback {
+            IdSource::Auto(idx) => {
+                let _ = write!(out, "#{idx}");
+            }
+            IdSource::CallSite(location, occurrence) => {
+                let _ = write!(
+                    out,
+                    "L{}:{}#{occurrence}",
+                    location.line(),
+                    location.column()
+                );
+            }
+            IdSource::Manual => {
+                let _ = write!(out, "{:x}", id.raw());
+            }
+        }
+    }
+
+    /// Register root of entity tree
+    pub(super) fn root(&mut self, id: ImmId) {
+        if self.paths.contains_key(&id) {
+            self.has_source = false;
+            return;
+        }
+        let mut path = String::new();
+        self.write_source(&mut path, IdSource::Manual, id);
+        self.paths.insert(id, path.into());
+    }
+
+    /// Register id prefix that was combined from `parent` prefix and recorded source
+    pub(super) fn prefix(&mut self, parent: ImmId, id: ImmId) {
+        if self.prefixes.contains_key(&id) {
+            self.has_source = false;
+            return;
+        }
+        let mut prefix = String::new();
+        if let Some(parent) = self.prefixes.get(&parent) {
+            prefix.push_str(parent);
+        }
+        self.write_source(&mut prefix, IdSource::Manual, id);
+        prefix.push(':');
+        self.prefixes.insert(id, prefix.into());
+    }
+
+    /// Retrieve path for child entity with `id`
+    pub(super) fn child(
+        &mut self,
+        parent: ImmId,
+        prefix: ImmId,
+        id: ImmId,
+        fallback: IdSource,
+    ) -> Arc<str> {
+        if let Some(path) = self.paths.get(&id) {
+            self.has_source = false;
+            return path.clone();
+        }
+
+        let mut path = String::new();
+        match self.paths.get(&parent) {
+            Some(parent) => path.push_str(parent),
+            None => {
+                let _ = write!(path, "{:x}", parent.raw());
+            }
+        }
+        path.push('/');
+        if let Some(prefix) = self.prefixes.get(&prefix) {
+            path.push_str(prefix);
+        }
+        self.write_source(&mut path, fallback, id);
+
+        let path: Arc<str> = path.into();
+        self.paths.insert(id, path.clone());
+        path
+    }
+
+    /// Retrieve path of already built entity
+    pub(super) fn get(&self, id: ImmId) -> Option<&Arc<str>> {
+        self.paths.get(&id)
+    }
+}
+
+/// Wraps path into component
+pub(super) fn component(path: Arc<str>) -> ImmDebugPath {
+    ImmDebugPath(path)
+}
+
+fn prune_debug_paths<Caps: Send + Sync + 'static>(
+    mut paths: ResMut<ImmDebugPaths<Caps>>,
+    mapping: Res<ImmediateModeEntityMapping<Caps>>,
+) {
+    // Keep paths for existing entities, so they are not rendered again each frame
+    if paths.paths.len() > 2 * mapping.id_to_entity.len() + 64 {
+        paths
+            .paths
+            .retain(|id, _| mapping.id_to_entity.contains_key(id));
+    }
+    paths.prefixes.clear();
+}
+
+/// Value from which [`crate::lid`] ids are constructed
+const LID_MARKER: i128 = 596784345354;
+
+/// Records values written by [`std::hash::Hash`] implementation in human readable form
+#[derive(Default)]
+struct DebugIdWriter {
+    text: String,
+    parts: Vec<(Range<usize>, Option<i128>)>,
+    /// Last write was string data that is followed by `0xff` terminator
+    str_written: bool,
+}
+
+impl DebugIdWriter {
+    fn clear(&mut self) {
+        self.text.clear();
+        self.parts.clear();
+        self.str_written = false;
+    }
+
+    fn int(&mut self, value: i128) {
+        self.str_written = false;
+        let start = self.text.len();
+        let _ = write!(self.text, "{value}");
+        self.parts.push((start..self.text.len(), Some(value)));
+    }
+
+    /// Renders recorded values as `name[value, ..]`
+    fn render(&self, out: &mut String) {
+        let mut parts = self.parts.as_slice();
+
+        // `lid!` values: (marker, line, column, ..)
+        if let [
+            (_, Some(marker)),
+            (line, Some(_)),
+            (column, Some(_)),
+            rest @ ..,
+        ] = parts
+            && (*marker == LID_MARKER || *marker == LID_MARKER as i32 as i128)
+        {
+            let _ = write!(
+                out,
+                "L{}:{}",
+                &self.text[line.clone()],
+                &self.text[column.clone()]
+            );
+            parts = rest;
+            if parts.is_empty() {
+                return;
+            }
+            out.push('[');
+        } else if let [(first, _), rest @ ..] = parts {
+            out.push_str(&self.text[first.clone()]);
+            parts = rest;
+            if parts.is_empty() {
+                return;
+            }
+            out.push('[');
+        } else {
+            out.push_str("()");
+            return;
+        }
+
+        for (idx, (part, _)) in parts.iter().enumerate() {
+            if idx > 0 {
+                out.push_str(", ");
+            }
+            out.push_str(&self.text[part.clone()]);
+        }
+        out.push(']');
+    }
+}
+
+impl Hasher for DebugIdWriter {
+    fn finish(&self) -> u64 {
+        0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let start = self.text.len();
+        match std::str::from_utf8(bytes) {
+            Ok(value) => {
+                self.text.push_str(value);
+                self.str_written = true;
+            }
+            Err(_) => {
+                for byte in bytes {
+                    let _ = write!(self.text, "{byte:02x}");
+                }
+                self.str_written = false;
+            }
+        }
+        self.parts.push((start..self.text.len(), None));
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        // `str` hash is terminated with 0xff
+        if self.str_written && i == 0xff {
+            self.str_written = false;
+            return;
+        }
+        self.int(i.into());
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.int(i.into());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.int(i.into());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.int(i.into());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.int(i as i128);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.int(i as i128);
+    }
+
+    fn write_i8(&mut self, i: i8) {
+        self.int(i.into());
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.int(i.into());
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.int(i.into());
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.int(i.into());
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.int(i);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.int(i as i128);
+    }
+}
@@ -0,0 +1,84 @@
+use std::marker::PhantomData;
+
+use bevy_ecs::{
+    entity::{Entity, EntityHashSet},
+    query::With,
+    resource::Resource,
+    schedule::{InternedScheduleLabel, IntoScheduleConfigs},
+    system::{Query, ResMut},
+};
+use bevy_platform::collections::HashMap;
+
+use crate::{ImmId, ImmMarker, ImmediateSystemSet, imm_rebuild_needed};
+
+pub fn init<Caps: Send + Sync + 'static>(app: &mut bevy_app::App, schedule: InternedScheduleLabel) {
+    app.add_systems(
+        schedule,
+        clean_detached_roots::<Caps>
+            .in_set(ImmediateSystemSet::<Caps>::default())
+            .run_if(imm_rebuild_needed::<Caps>),
+    );
+    app.insert_resource(DetachedRoots::<Caps>::default());
+}
+
+/// Entities that were built by scope, but are not its descendants in [`bevy_ecs::hierarchy::Children`]
+/// hierarchy (unrooted entity trees and portals)
+///
+/// Used to keep them alive together with entity that built them when its subtree is not rebuilt.
+#[derive(Resource)]
+pub(super) struct DetachedRoots<Caps> {
+    /// Roots by [`ImmId`] of owner that built them
+    roots: HashMap<ImmId, Roots>,
+    _ph: PhantomData<Caps>,
+}
+
+impl<Caps> Default for DetachedRoots<Caps> {
+    fn default() -> Self {
+        Self {
+            roots: Default::default(),
+            _ph: PhantomData,
+        }
+    }
+}
+
+struct Roots {
+    /// Upkeep iteration in which roots were built
+    iteration: u32,
+    entities: EntityHashSet,
+}
+
+impl<Caps> DetachedRoots<Caps> {
+    /// Record that `owner` built `entity` during given upkeep iteration
+    ///
+    /// Roots recorded during previous iterations are forgotten.
+    pub(super) fn record(&mut self, owner: ImmId, entity: Entity, iteration: u32) {
+        let roots = self.roots.entry(owner).or_insert_with(|| Roots {
+            iteration,
+            entities: EntityHashSet::default(),
+        });
+        if roots.iteration != iteration {
+            roots.iteration = iteration;
+            roots.entities.clear();
+        }
+        // The same tree may be built multiple times during frame
+        roots.entities.insert(entity);
+    }
+
+    /// Roots that were built by `owner` last time it was built
+    pub(super) fn get(&self, owner: ImmId) -> impl Iterator<Item = Entity> + '_ {
+        self.roots
+            .get(&owner)
+            .into_iter()
+            .flat_map(|roots| roots.entities.iter().copied())
+    }
+}
+
+fn clean_detached_roots<Caps: Send + Sync + 'static>(
+    mut detached: ResMut<DetachedRoots<Caps>>,
+    query: Query<(), With<ImmMarker<Caps>>>,
+) {
+    detached.roots.retain(|_, roots| {
+        roots.entities.retain(|&entity| query.contains(entity));
+        !roots.entities.is_empty()
+    });
+}
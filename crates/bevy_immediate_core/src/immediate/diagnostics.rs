@@ -0,0 +1,461 @@
+use std::{marker::PhantomData, panic::Location, sync::Arc};
+
+use bevy_ecs::{entity::Entity, resource::Resource};
+
+use crate::ImmIdCollision;
+
+/// Detects misuse of immediate mode API for capability set `Caps`
+///
+/// Diagnostics are detected only in debug builds (`debug_assertions`).
+/// Each kind of diagnostic is reported once per call site.
+///
+/// Must be added after [`crate::BevyImmediatePlugin`].
+///
+/// ```ignore
+/// // Fail tests and CI runs on any detected misuse
+/// app.add_plugins(ImmDiagnosticsPlugin::<CapsUi>::new().with_mode(ImmDiagnosticsMode::Panic));
+/// ```
+pub struct ImmDiagnosticsPlugin<Caps> {
+    mode: ImmDiagnosticsMode,
+    checks: ImmDiagnosticChecks,
+    _ph: PhantomData<Caps>,
+}
+
+impl<Caps> ImmDiagnosticsPlugin<Caps> {
+    /// Construct plugin that reports diagnostics as warnings
+    pub fn new() -> Self {
+        Self {
+            mode: ImmDiagnosticsMode::Warn,
+            checks: ImmDiagnosticChecks::default(),
+            _ph: PhantomData,
+        }
+    }
+
+    /// Set how detected diagnostics are reported
+    pub fn with_mode(mut self, mode: ImmDiagnosticsMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Select which checks are executed
+    pub fn with_checks(mut self, checks: ImmDiagnosticChecks) -> Self {
+        self.checks = checks;
+        self
+    }
+}
+
+impl<Caps> Default for ImmDiagnosticsPlugin<Caps> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Caps: Send + Sync + 'static> bevy_app::Plugin for ImmDiagnosticsPlugin<Caps> {
+    fn build(&self, app: &mut bevy_app::App) {
+        let schedule = app
+            .world()
+            .get_resource::<crate::ImmSchedules<Caps>>()
+            .expect("BevyImmediatePlugin must be added before ImmDiagnosticsPlugin")
+            .upkeep();
+
+        app.insert_resource(ImmDiagnostics::<Caps> {
+            mode: self.mode,
+            checks: self.checks,
+            collected: Vec::new(),
+            #[cfg(debug_assertions)]
+            frame: Default::default(),
+            _ph: PhantomData,
+        });
+
+        #[cfg(debug_assertions)]
+        debug::init::<Caps>(app, schedule);
+        #[cfg(not(debug_assertions))]
+        let _ = schedule;
+    }
+}
+
+/// How detected diagnostics are reported
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImmDiagnosticsMode {
+    /// Log warning
+    Warn,
+    /// Panic with diagnostic message
+    Panic,
+    /// Store diagnostics in [`ImmDiagnostics`] resource
+    Collect,
+}
+
+/// Checks executed by [`ImmDiagnosticsPlugin`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImmDiagnosticChecks {
+    /// Component is present on entity, but capabilities have not requested access to it
+    pub missing_capability_access: bool,
+    /// Capability data accessed for entity that is not managed by immediate mode
+    pub non_immediate_entity: bool,
+    /// Entity is reparented during this amount of consecutive frames. `0` disables check
+    pub reparent_churn_frames: u32,
+    /// Two entities were built with the same [`ImmId`]
+    pub id_collisions: bool,
+    /// Single `.ch()` call site generated multiple auto ids under the same parent during frame
+    ///
+    /// Auto ids shift when items are inserted or removed, use `.ch_id()` or
+    /// [`crate::Imm::for_each_keyed`] for lists. Helper functions that call `.ch()`
+    /// should be marked with `#[track_caller]` to avoid false positives.
+    pub auto_id_in_loop: bool,
+}
+
+impl Default for ImmDiagnosticChecks {
+    fn default() -> Self {
+        Self {
+            missing_capability_access: true,
+            non_immediate_entity: true,
+            reparent_churn_frames: 3,
+            id_collisions: true,
+            auto_id_in_loop: true,
+        }
+    }
+}
+
+/// Kind of detected immediate mode API misuse
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImmDiagnosticKind {
+    /// Component is present on entity, but no capability requested access to it
+    MissingCapabilityAccess {
+        /// Component type name
+        component: &'static str,
+        /// Mutable access was requested
+        write: bool,
+    },
+    /// Entity is not managed by immediate mode of this capability set
+    NonImmediateEntity,
+    /// Entity was reparented during this amount of consecutive frames
+    ReparentChurn {
+        /// Amount of consecutive frames
+        frames: u32,
+    },
+    /// Two entities were built with the same [`ImmId`]
+    IdCollision(ImmIdCollision),
+    /// Single call site generated multiple auto ids under the same parent during frame
+    AutoIdInLoop,
+}
+
+impl std::fmt::Display for ImmDiagnosticKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImmDiagnosticKind::MissingCapabilityAccess { component, write } => {
+                let access = if *write { "write" } else { "read" };
+                write!(
+                    f,
+                    "{component} {access} access was not requested by any capability"
+                )
+            }
+            ImmDiagnosticKind::NonImmediateEntity => {
+                write!(f, "entity is not managed by immediate mode")
+            }
+            ImmDiagnosticKind::ReparentChurn { frames } => {
+                write!(
+                    f,
+                    "entity was reparented during {frames} consecutive frames"
+                )
+            }
+            ImmDiagnosticKind::IdCollision(collision) => {
+                write!(f, "id collision (first built by {})", collision.first)
+            }
+            ImmDiagnosticKind::AutoIdInLoop => write!(
+                f,
+                "auto id generated multiple times under the same parent, use `.ch_id()` or `for_each_keyed`"
+            ),
+        }
+    }
+}
+
+/// Detected immediate mode API misuse
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImmDiagnostic {
+    /// What was detected
+    pub kind: ImmDiagnosticKind,
+    /// Source code location that caused diagnostic
+    pub location: &'static Location<'static>,
+    /// Entity for which diagnostic was detected
+    pub entity: Option<Entity>,
+    /// Human readable id path of entity. See `ImmDebugPath`
+    pub path: Option<Arc<str>>,
+}
+
+impl std::fmt::Display for ImmDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Immediate mode: {} at {}", self.kind, self.location)?;
+        if let Some(entity) = self.entity {
+            write!(f, " ({entity}")?;
+            if let Some(path) = &self.path {
+                write!(f, " {path}")?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// Diagnostics detected for capability set `Caps`. See [`ImmDiagnosticsPlugin`]
+#[derive(Resource)]
+pub struct ImmDiagnostics<Caps> {
+    mode: ImmDiagnosticsMode,
+    checks: ImmDiagnosticChecks,
+    collected: Vec<ImmDiagnostic>,
+
+    #[cfg(debug_assertions)]
+    frame: debug::FrameDiagnostics,
+
+    _ph: PhantomData<Caps>,
+}
+
+impl<Caps> ImmDiagnostics<Caps> {
+    /// How detected diagnostics are reported
+    pub fn mode(&self) -> ImmDiagnosticsMode {
+        self.mode
+    }
+
+    /// Executed checks
+    pub fn checks(&self) -> &ImmDiagnosticChecks {
+        &self.checks
+    }
+
+    /// Diagnostics collected in [`ImmDiagnosticsMode::Collect`] mode
+    pub fn diagnostics(&self) -> &[ImmDiagnostic] {
+        &self.collected
+    }
+
+    /// No diagnostics have been collected
+    pub fn is_empty(&self) -> bool {
+        self.collected.is_empty()
+    }
+
+    /// Take collected diagnostics
+    ///
+    /// Already reported call sites will not be reported again.
+    pub fn take(&mut self) -> Vec<ImmDiagnostic> {
+        std::mem::take(&mut self.collected)
+    }
+}
+
+/// Result of capability data access check
+#[cfg(debug_assertions)]
+pub(super) enum CapAccess {
+    Accessible,
+    NotRequested,
+    NotImmediate,
+}
+
+#[cfg(debug_assertions)]
+pub(super) mod debug {
+    use std::{
+        mem::Discriminant,
+        panic::Location,
+        sync::{Arc, Mutex, PoisonError},
+    };
+
+    use bevy_ecs::{
+        entity::Entity,
+        schedule::{InternedScheduleLabel, IntoScheduleConfigs},
+        system::{Res, ResMut},
+    };
+    use bevy_platform::collections::{HashMap, HashSet};
+
+    use super::{CapAccess, ImmDiagnostic, ImmDiagnosticKind, ImmDiagnostics, ImmDiagnosticsMode};
+    use crate::{ImmId, ImmIdCollisions, ImmediateSystemSet, immediate::id_collision};
+
+    pub fn init<Caps: Send + Sync + 'static>(
+        app: &mut bevy_app::App,
+        schedule: InternedScheduleLabel,
+    ) {
+        app.add_systems(
+            schedule,
+            report_diagnostics::<Caps>
+                .in_set(ImmediateSystemSet::<Caps>::default())
+                .after(id_collision::debug::report_id_collisions::<Caps>),
+        );
+    }
+
+    /// State tracked during frame
+    #[derive(Default)]
+    pub struct FrameDiagnostics {
+        /// Diagnostics waiting to be reported. Capability access is checked through shared reference
+        pending: Mutex<Vec<ImmDiagnostic>>,
+        reported: HashSet<(Discriminant<ImmDiagnosticKind>, &'static Location<'static>)>,
+        /// Consecutive frames during which entity was reparented
+        reparent_streaks: HashMap<Entity, u32>,
+        reparented: HashSet<Entity>,
+        /// First auto id generated for parent id, id prefix and call site
+        auto_ids: HashMap<(ImmId, ImmId, &'static Location<'static>), ImmId>,
+        /// [`ImmIdCollisions::stored_total`] when collisions were read last time
+        collisions_seen: usize,
+    }
+
+    impl<Caps> ImmDiagnostics<Caps> {
+        fn push(&self, diagnostic: ImmDiagnostic) {
+            self.frame
+                .pending
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(diagnostic);
+        }
+
+        /// Record result of capability data access
+        pub(in crate::immediate) fn cap_access<T>(
+            &self,
+            access: CapAccess,
+            write: bool,
+            location: &'static Location<'static>,
+            entity: Entity,
+            path: Option<Arc<str>>,
+        ) {
+            let kind = match access {
+                CapAccess::Accessible => return,
+                CapAccess::NotRequested if self.checks.missing_capability_access => {
+                    ImmDiagnosticKind::MissingCapabilityAccess {
+                        component: std::any::type_name::<T>(),
+                        write,
+                    }
+                }
+                CapAccess::NotImmediate if self.checks.non_immediate_entity => {
+                    ImmDiagnosticKind::NonImmediateEntity
+                }
+                _ => return,
+            };
+            self.push(ImmDiagnostic {
+                kind,
+                location,
+                entity: Some(entity),
+                path,
+            });
+        }
+
+        /// Record that entity that is not managed by immediate mode is accessed as entity
+        pub(in crate::immediate) fn non_immediate_entity(
+            &self,
+            location: &'static Location<'static>,
+            entity: Entity,
+        ) {
+            if !self.checks.non_immediate_entity {
+                return;
+            }
+            self.push(ImmDiagnostic {
+                kind: ImmDiagnosticKind::NonImmediateEntity,
+                location,
+                entity: Some(entity),
+                path: None,
+            });
+        }
+
+        /// Record that entity was moved to different parent
+        pub(in crate::immediate) fn reparented(
+            &mut self,
+            location: &'static Location<'static>,
+            entity: Entity,
+            path: &Arc<str>,
+        ) {
+            let frames = self.checks.reparent_churn_frames;
+            if frames == 0 || !self.frame.reparented.insert(entity) {
+                return;
+            }
+            let streak = self.frame.reparent_streaks.entry(entity).or_default();
+            *streak += 1;
+            if *streak == frames {
+                self.push(ImmDiagnostic {
+                    kind: ImmDiagnosticKind::ReparentChurn { frames },
+                    location,
+                    entity: Some(entity),
+                    path: Some(path.clone()),
+                });
+            }
+        }
+
+        /// Record that auto id was generated
+        pub(in crate::immediate) fn auto_id(
+            &mut self,
+            parent: ImmId,
+            id_pref: ImmId,
+            id: ImmId,
+            location: &'static Location<'static>,
+            entity: Entity,
+            path: &Arc<str>,
+        ) {
+            if !self.checks.auto_id_in_loop {
+                return;
+            }
+            // The same id is generated again when tree is built multiple times during frame
+            let first_id = *self
+                .frame
+                .auto_ids
+                .entry((parent, id_pref, location))
+                .or_insert(id);
+            if first_id == id {
+                return;
+            }
+            self.push(ImmDiagnostic {
+                kind: ImmDiagnosticKind::AutoIdInLoop,
+                location,
+                entity: Some(entity),
+                path: Some(path.clone()),
+            });
+        }
+    }
+
+    fn report_diagnostics<Caps: Send + Sync + 'static>(
+        mut diagnostics: ResMut<ImmDiagnostics<Caps>>,
+        collisions: Res<ImmIdCollisions<Caps>>,
+    ) {
+        let diagnostics = &mut *diagnostics;
+        let frame = &mut diagnostics.frame;
+        let pending = frame
+            .pending
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        // Collisions may have been taken from resource, new ones are at the end
+        let stored_total = collisions.stored_total();
+        let collisions = collisions.collisions();
+        let new = (stored_total - frame.collisions_seen).min(collisions.len());
+        if diagnostics.checks.id_collisions {
+            pending.extend(
+                collisions[collisions.len() - new..]
+                    .iter()
+                    .map(|collision| ImmDiagnostic {
+                        kind: ImmDiagnosticKind::IdCollision(collision.clone()),
+                        location: collision.second.location,
+                        entity: Some(collision.second.entity),
+                        path: collision.second.path.clone(),
+                    }),
+            );
+        }
+        frame.collisions_seen = stored_total;
+
+        // Streak ends when entity was not reparented during this frame
+        frame
+            .reparent_streaks
+            .retain(|entity, _| frame.reparented.contains(entity));
+        frame.reparented.clear();
+        frame.auto_ids.clear();
+
+        for diagnostic in pending.drain(..) {
+            let key = (
+                std::mem::discriminant(&diagnostic.kind),
+                diagnostic.location,
+            );
+            if !frame.reported.insert(key) {
+                continue;
+            }
+
+            match diagnostics.mode {
+                ImmDiagnosticsMode::Warn => {
+                    // Id collisions are already logged
+                    if !matches!(diagnostic.kind, ImmDiagnosticKind::IdCollision(_)) {
+                        log::warn!("{diagnostic}");
+                    }
+                }
+                ImmDiagnosticsMode::Panic => panic!("{diagnostic}"),
+                ImmDiagnosticsMode::Collect => diagnostics.collected.push(diagnostic),
+            }
+        }
+    }
+}
@@ -39,6 +39,9 @@ fn on_imm_marker_added<Caps: Send + Sync + 'static>(
     let entity = trigger.event().entity;
     if let Ok(marker) = marker.get(entity) {
         let old = mapping.id_to_entity.insert(marker.id, entity);
+
+        // In debug builds collisions are reported with call sites. See `ImmIdCollisions`
+        #[cfg(not(debug_assertions))]
         if let Some(old) = old {
             log::warn!(
                 "Immediate mode entity id collision for entities {} and {}",
@@ -46,6 +49,8 @@ fn on_imm_marker_added<Caps: Send + Sync + 'static>(
                 old
             );
         }
+        #[cfg(debug_assertions)]
+        let _ = old;
     }
 }
 
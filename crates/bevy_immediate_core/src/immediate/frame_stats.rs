@@ -0,0 +1,82 @@
+use std::marker::PhantomData;
+
+use bevy_ecs::{
+    resource::Resource,
+    schedule::{InternedScheduleLabel, IntoScheduleConfigs},
+    system::{Res, ResMut},
+};
+
+use crate::{ImmediateSystemSet, immediate::cached_hash::CachedHash};
+
+pub fn init<Caps: Send + Sync + 'static>(app: &mut bevy_app::App, schedule: InternedScheduleLabel) {
+    app.insert_resource(ImmFrameStats::<Caps>::default());
+    app.add_systems(
+        schedule,
+        finish_frame_stats::<Caps>.after(ImmediateSystemSet::<Caps>::default()),
+    );
+}
+
+/// Statistics about work done by immediate mode during frame
+///
+/// Counters are collected during frame and become available through [`Self::last_frame`]
+/// after [`ImmediateSystemSet`] systems are executed in upkeep schedule
+/// ([`crate::ImmSchedules::upkeep`]).
+///
+/// Useful for performance budgets.
+#[derive(Resource)]
+pub struct ImmFrameStats<Caps> {
+    current: ImmFrameCounters,
+    last_frame: ImmFrameCounters,
+    _ph: PhantomData<Caps>,
+}
+
+impl<Caps> Default for ImmFrameStats<Caps> {
+    fn default() -> Self {
+        Self {
+            current: Default::default(),
+            last_frame: Default::default(),
+            _ph: PhantomData,
+        }
+    }
+}
+
+/// Counters of work done by immediate mode during single frame
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ImmFrameCounters {
+    /// Entities built with `.ch()` and similar functions
+    pub visited: u32,
+    /// Entities kept alive without being built (`memo`, `keep_children_alive`)
+    pub kept: u32,
+    /// Entities spawned
+    pub spawned: u32,
+    /// Entities that were moved to different parent entity
+    pub reparented: u32,
+    /// Entities despawned by upkeep because they were not built
+    pub despawned: u32,
+    /// Amount of cached hash entries at the end of frame
+    pub cached_hashes: u32,
+    /// Amount of parent entities whose children were reordered by `layout_order`
+    pub layout_order_sorts: u32,
+}
+
+impl<Caps> ImmFrameStats<Caps> {
+    /// Counters for last completed frame
+    pub fn last_frame(&self) -> ImmFrameCounters {
+        self.last_frame
+    }
+
+    /// Counters for frame that is in progress
+    ///
+    /// Useful for capabilities to report their work
+    pub fn current_mut(&mut self) -> &mut ImmFrameCounters {
+        &mut self.current
+    }
+}
+
+fn finish_frame_stats<Caps: Send + Sync + 'static>(
+    mut stats: ResMut<ImmFrameStats<Caps>>,
+    cached_hash: Res<CachedHash<Caps>>,
+) {
+    stats.current.cached_hashes = cached_hash.len() as u32;
+    stats.last_frame = std::mem::take(&mut stats.current);
+}
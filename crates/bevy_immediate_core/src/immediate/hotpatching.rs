@@ -1,10 +1,19 @@
+use std::marker::PhantomData;
+
 use bevy_ecs::{message::MessageReader, resource::Resource, system::ResMut};
 
-pub(super) fn init(app: &mut bevy_app::App) {
+pub(super) fn init<Caps: Send + Sync + 'static>(app: &mut bevy_app::App) {
     if !app.world_mut().contains_resource::<HotpatchingCounter>() {
         app.insert_resource(HotpatchingCounter { hotpatch: 0 });
+        app.init_resource::<ImmHotpatchMode>();
         app.add_systems(bevy_app::PreUpdate, hotpatch_listener);
     }
+
+    app.insert_resource(HotpatchApplied::<Caps> {
+        hotpatch: 0,
+        rerun_iteration: None,
+        _ph: PhantomData,
+    });
 }
 
 fn hotpatch_listener(
@@ -32,3 +41,42 @@ impl HotpatchingCounter {
         self.hotpatch
     }
 }
+
+/// Describes how immediate mode entity tree is updated after hotpatch
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ImmHotpatchMode {
+    /// Root ids are combined with hotpatch count, so whole entity tree is respawned
+    #[default]
+    Respawn,
+    /// Ids are kept stable. During first build after hotpatch `on_spawn_insert*` and
+    /// `on_spawn_apply_commands*` functions are executed again for existing entities
+    /// that opted in with [`crate::ImmEntity::rerun_spawn_hooks_on_hotpatch`].
+    ///
+    /// Edited styles of opted in entities are applied while interactive state
+    /// (text input contents, scroll positions, etc.) of all entities is preserved.
+    RerunSpawnHooks,
+}
+
+/// Hotpatch count for which entity tree has been built
+#[derive(Resource)]
+pub(super) struct HotpatchApplied<Caps> {
+    hotpatch: u32,
+    rerun_iteration: Option<u32>,
+    _ph: PhantomData<Caps>,
+}
+
+impl<Caps> HotpatchApplied<Caps> {
+    /// Record that entity tree is built during given upkeep iteration
+    pub(super) fn build(&mut self, counter: &HotpatchingCounter, iteration: u32) {
+        if self.hotpatch != counter.hotpatch {
+            self.hotpatch = counter.hotpatch;
+            self.rerun_iteration = Some(iteration);
+        }
+    }
+
+    /// Spawn hooks should be executed for existing entities during given upkeep iteration
+    #[inline]
+    pub(super) fn rerun_spawn_hooks(&self, mode: ImmHotpatchMode, iteration: u32) -> bool {
+        mode == ImmHotpatchMode::RerunSpawnHooks && self.rerun_iteration == Some(iteration)
+    }
+}
@@ -1,9 +1,14 @@
+use std::{marker::PhantomData, panic::Location};
+
+use bevy_ecs::resource::Resource;
+use bevy_platform::collections::HashMap;
+
 use crate::{CapSet, Imm};
 
 /// Unique id for immediate mode entities.
 ///
 /// Ids are used to track entities managed by immediate mode logic.
-#[derive(Hash, Clone, Copy, PartialEq, Eq)]
+#[derive(Hash, Clone, Copy, PartialEq, Eq, Debug)]
 pub struct ImmId {
     id: u64,
 }
@@ -56,6 +61,43 @@ where
     }
 }
 
+/// How [`ImmIdBuilder::Auto`] ids are generated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ImmAutoIdMode {
+    /// Use index of auto generated id in current scope
+    ///
+    /// Building child conditionally shifts ids of all following siblings that
+    /// use auto generated ids.
+    #[default]
+    Index,
+    /// Use source code location of `.ch()` call and amount of previous
+    /// auto generated ids from the same location in current scope
+    ///
+    /// Ids stay stable when siblings are built conditionally, like with [`crate::lch`].
+    /// Helper functions that call `.ch()` should be marked with `#[track_caller]`.
+    CallSite,
+}
+
+/// Default [`ImmAutoIdMode`] of entity trees built with capability set `Caps`
+///
+/// Set with [`crate::BevyImmediatePlugin::with_auto_id_mode`].
+/// Can be changed for part of entity tree with [`Imm::set_auto_id_mode`].
+#[derive(Resource)]
+pub struct ImmAutoIdConfig<Caps> {
+    pub(super) mode: ImmAutoIdMode,
+    pub(super) _ph: PhantomData<Caps>,
+}
+
+impl<Caps> ImmAutoIdConfig<Caps> {
+    /// Default auto id generation mode
+    pub fn mode(&self) -> ImmAutoIdMode {
+        self.mode
+    }
+}
+
+/// Amount of auto generated ids per scope and call site during current build
+pub(super) type CallSiteOccurrences = HashMap<(ImmId, ImmId, &'static Location<'static>), u32>;
+
 /// Can be used to construct unique id for new entity
 pub enum ImmIdBuilder {
     /// Use auto generated id ()
@@ -70,8 +112,17 @@ pub enum ImmIdBuilder {
 }
 
 impl ImmIdBuilder {
+    #[track_caller]
     pub(super) fn resolve<Caps: CapSet>(self, sui: &mut Imm<Caps>) -> ImmId {
         match self {
+            ImmIdBuilder::Auto if sui.current.auto_id_mode == ImmAutoIdMode::CallSite => {
+                const CALL_SITE: u32 = 584729183;
+                let location = std::panic::Location::caller();
+                let occurrence = sui.call_site_occurrence(location);
+                sui.current
+                    .id
+                    .with((CALL_SITE, sui.current.id_pref, location, occurrence))
+            }
             ImmIdBuilder::Auto => {
                 // Auto increment id only when adding entity with auto generated id
                 // All entities that are not permanent children of parent should have
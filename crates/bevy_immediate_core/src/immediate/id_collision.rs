@@ -0,0 +1,262 @@
+use std::{borrow::Cow, marker::PhantomData, panic::Location, sync::Arc};
+
+use bevy_ecs::{
+    component::Component, entity::Entity, resource::Resource, schedule::InternedScheduleLabel,
+};
+
+use crate::ImmId;
+
+pub fn init<Caps: Send + Sync + 'static>(app: &mut bevy_app::App, schedule: InternedScheduleLabel) {
+    app.insert_resource(ImmIdCollisions::<Caps>::default());
+
+    #[cfg(debug_assertions)]
+    debug::init::<Caps>(app, schedule);
+    #[cfg(not(debug_assertions))]
+    let _ = schedule;
+}
+
+/// Label provided by [`crate::ImmEntity::debug_label`]
+///
+/// Added to immediate mode entities only in debug builds (`debug_assertions`).
+/// Label stays on entity until it is replaced by another label.
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct ImmDebugLabel(pub Cow<'static, str>);
+
+impl ImmDebugLabel {
+    /// Label as string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Call site where immediate mode entity was built
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImmCallSite {
+    /// Entity that was built
+    pub entity: Entity,
+    /// Source code location of `.ch()`, `.ch_id()` or `.ch_with_manual_id()` call
+    pub location: &'static Location<'static>,
+    /// Label provided by [`crate::ImmEntity::debug_label`]
+    pub label: Option<Cow<'static, str>>,
+    /// Human readable id path. See `ImmDebugPath`
+    pub path: Option<Arc<str>>,
+}
+
+impl std::fmt::Display for ImmCallSite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.entity)?;
+        if let Some(path) = &self.path {
+            write!(f, " {path}")?;
+        }
+        write!(f, " at {}", self.location)?;
+        if let Some(label) = &self.label {
+            write!(f, " ({label})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Two entities were built with the same [`ImmId`] during the same frame
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImmIdCollision {
+    /// Colliding id
+    pub id: ImmId,
+    /// Call site that built entity with this id first
+    pub first: ImmCallSite,
+    /// Call site that built entity with the same id afterwards
+    pub second: ImmCallSite,
+}
+
+/// Id collisions detected for capability set `Caps`
+///
+/// Collisions are collected only in debug builds (`debug_assertions`).
+/// Each unique pair of call sites is reported once until [`Self::clear`] is called.
+/// At most [`Self::MAX_COLLISIONS`] collisions are stored, further collisions are only logged
+/// until stored collisions are taken.
+///
+/// Useful to fail tests when immediate mode code contains id collisions.
+#[derive(Resource)]
+pub struct ImmIdCollisions<Caps> {
+    collisions: Vec<ImmIdCollision>,
+    stored_total: usize,
+
+    #[cfg(debug_assertions)]
+    frame: debug::FrameVisits,
+
+    _ph: PhantomData<Caps>,
+}
+
+impl<Caps> Default for ImmIdCollisions<Caps> {
+    fn default() -> Self {
+        Self {
+            collisions: Default::default(),
+            stored_total: 0,
+            #[cfg(debug_assertions)]
+            frame: Default::default(),
+            _ph: PhantomData,
+        }
+    }
+}
+
+impl<Caps> ImmIdCollisions<Caps> {
+    /// Maximum amount of stored collisions
+    pub const MAX_COLLISIONS: usize = 256;
+
+    /// Detected collisions
+    pub fn collisions(&self) -> &[ImmIdCollision] {
+        &self.collisions
+    }
+
+    /// No collisions have been detected
+    pub fn is_empty(&self) -> bool {
+        self.collisions.is_empty()
+    }
+
+    /// Amount of collisions stored since start
+    ///
+    /// Counter is not reduced by [`Self::take`] and [`Self::clear`], so it can be used
+    /// to find collisions that were stored since counter was read last time.
+    pub fn stored_total(&self) -> usize {
+        self.stored_total
+    }
+
+    /// Take detected collisions
+    ///
+    /// Already reported call site pairs will not be reported again.
+    pub fn take(&mut self) -> Vec<ImmIdCollision> {
+        std::mem::take(&mut self.collisions)
+    }
+
+    /// Remove detected collisions and forget already reported call site pairs
+    pub fn clear(&mut self) {
+        self.collisions.clear();
+        #[cfg(debug_assertions)]
+        self.frame.reported.clear();
+    }
+}
+
+#[cfg(debug_assertions)]
+pub(super) mod debug {
+    use std::{borrow::Cow, panic::Location, sync::Arc};
+
+    use bevy_ecs::{
+        entity::Entity,
+        schedule::{InternedScheduleLabel, IntoScheduleConfigs},
+        system::ResMut,
+    };
+    use bevy_platform::collections::{HashMap, HashSet, hash_map::Entry};
+
+    use super::{ImmCallSite, ImmIdCollision, ImmIdCollisions};
+    use crate::{ImmId, ImmediateSystemSet};
+
+    pub fn init<Caps: Send + Sync + 'static>(
+        app: &mut bevy_app::App,
+        schedule: InternedScheduleLabel,
+    ) {
+        app.add_systems(
+            schedule,
+            report_id_collisions::<Caps>.in_set(ImmediateSystemSet::<Caps>::default()),
+        );
+    }
+
+    /// Entities built during current frame
+    #[derive(Default)]
+    pub struct FrameVisits {
+        visited: HashMap<ImmId, ImmCallSite>,
+        duplicates: Vec<(ImmId, ImmCallSite)>,
+        /// Amount of call sites is finite, so set stays bounded
+        pub(super) reported: HashSet<(&'static Location<'static>, &'static Location<'static>)>,
+    }
+
+    /// Identifies record of entity visit in current frame
+    #[derive(Clone, Copy)]
+    pub enum DebugVisit {
+        First(ImmId),
+        Duplicate(usize),
+    }
+
+    impl<Caps> ImmIdCollisions<Caps> {
+        /// Record that entity was built during this frame
+        pub(in crate::immediate) fn visit(
+            &mut self,
+            id: ImmId,
+            entity: Entity,
+            location: &'static Location<'static>,
+            path: Arc<str>,
+        ) -> DebugVisit {
+            let call_site = ImmCallSite {
+                entity,
+                location,
+                label: None,
+                path: Some(path),
+            };
+            match self.frame.visited.entry(id) {
+                Entry::Vacant(entry) => {
+                    entry.insert(call_site);
+                    DebugVisit::First(id)
+                }
+                // The same tree built again during frame (attach on insert, multiple builds)
+                Entry::Occupied(entry)
+                    if entry.get().entity == entity && entry.get().location == location =>
+                {
+                    DebugVisit::First(id)
+                }
+                Entry::Occupied(_) => {
+                    self.frame.duplicates.push((id, call_site));
+                    DebugVisit::Duplicate(self.frame.duplicates.len() - 1)
+                }
+            }
+        }
+
+        /// Attach label to recorded visit
+        pub(in crate::immediate) fn set_label(
+            &mut self,
+            visit: DebugVisit,
+            label: Cow<'static, str>,
+        ) {
+            let call_site = match visit {
+                DebugVisit::First(id) => self.frame.visited.get_mut(&id),
+                DebugVisit::Duplicate(idx) => {
+                    self.frame.duplicates.get_mut(idx).map(|(_, site)| site)
+                }
+            };
+            if let Some(call_site) = call_site {
+                call_site.label = Some(label);
+            }
+        }
+    }
+
+    pub(in crate::immediate) fn report_id_collisions<Caps: Send + Sync + 'static>(
+        mut state: ResMut<ImmIdCollisions<Caps>>,
+    ) {
+        let state = &mut *state;
+        let frame = &mut state.frame;
+
+        for (id, second) in frame.duplicates.drain(..) {
+            let Some(first) = frame.visited.get(&id) else {
+                continue;
+            };
+
+            if !frame.reported.insert((first.location, second.location)) {
+                continue;
+            }
+
+            log::warn!(
+                "Immediate mode entity id collision. First built by {}, then by {}",
+                first,
+                second
+            );
+
+            if state.collisions.len() < ImmIdCollisions::<Caps>::MAX_COLLISIONS {
+                state.collisions.push(ImmIdCollision {
+                    id,
+                    first: first.clone(),
+                    second,
+                });
+                state.stored_total += 1;
+            }
+        }
+
+        frame.visited.clear();
+    }
+}
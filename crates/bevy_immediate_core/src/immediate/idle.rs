@@ -0,0 +1,232 @@
+use std::marker::PhantomData;
+
+use bevy_ecs::{
+    change_detection::DetectChanges,
+    message::{Message, MessageReader},
+    resource::Resource,
+    schedule::{InternedScheduleLabel, IntoScheduleConfigs, SystemSet},
+    system::{Res, ResMut},
+};
+
+use crate::ImmediateSystemSet;
+
+pub fn init<Caps: Send + Sync + 'static>(app: &mut bevy_app::App, schedule: InternedScheduleLabel) {
+    app.insert_resource(ImmFrameSkip::<Caps>::default());
+    app.add_systems(
+        schedule,
+        reset_frame_skip::<Caps>.after(ImmediateSystemSet::<Caps>::default()),
+    );
+}
+
+/// Tracks if immediate mode entity trees are rebuilt during current frame
+///
+/// When frame is skipped, entities that were not built are not despawned
+/// and existing trees are left intact. Skipping applies to all entity trees of capability set,
+/// so all systems that build them must use [`imm_rebuild_needed`] run condition.
+///
+/// Frame can be skipped manually with [`Self::skip_frame`] or automatically by [`ImmIdlePlugin`].
+/// Use [`crate::ImmCtx::skip_root`] to skip rebuilding single entity tree.
+#[derive(Resource)]
+pub struct ImmFrameSkip<Caps> {
+    skipped: bool,
+    rebuild_requested: bool,
+    _ph: PhantomData<Caps>,
+}
+
+impl<Caps> Default for ImmFrameSkip<Caps> {
+    fn default() -> Self {
+        Self {
+            skipped: false,
+            rebuild_requested: false,
+            _ph: PhantomData,
+        }
+    }
+}
+
+impl<Caps> ImmFrameSkip<Caps> {
+    /// Declare that entity tree is not rebuilt during current frame
+    pub fn skip_frame(&mut self) {
+        self.skipped = true;
+    }
+
+    /// Is current frame skipped
+    pub fn is_skipped(&self) -> bool {
+        self.skipped
+    }
+
+    /// Request [`ImmIdlePlugin`] to rebuild entity tree
+    ///
+    /// Useful when state that is not watched by [`ImmIdlePlugin`] changes.
+    pub fn request_rebuild(&mut self) {
+        self.rebuild_requested = true;
+    }
+}
+
+/// Run condition that returns `true` when entity tree should be rebuilt during current frame
+///
+/// Add it to systems that build immediate mode entity tree when [`ImmIdlePlugin`] is used.
+pub fn imm_rebuild_needed<Caps: Send + Sync + 'static>(skip: Res<ImmFrameSkip<Caps>>) -> bool {
+    !skip.skipped
+}
+
+fn reset_frame_skip<Caps: Send + Sync + 'static>(mut skip: ResMut<ImmFrameSkip<Caps>>) {
+    skip.skipped = false;
+}
+
+/// Skips entity tree rebuilds while application is idle
+///
+/// Frame is rebuilt only if watched resources changed, watched messages were sent or
+/// rebuild was requested with [`ImmFrameSkip::request_rebuild`].
+///
+/// Activity is detected in build schedule ([`crate::ImmSchedules::build`]) by
+/// [`ImmIdleSystems`]. Systems that build entity tree should run after [`ImmIdleSystems::Decide`]
+/// and use [`imm_rebuild_needed`] run condition. Changes made by systems that run
+/// before [`ImmIdleSystems::Watch`] are reflected during the same frame.
+///
+/// Must be added after [`crate::BevyImmediatePlugin`].
+///
+/// ```ignore
+/// app.add_plugins(
+///     ImmIdlePlugin::<CapsUi>::new()
+///         .watch_message::<PointerInput>()
+///         .watch_message::<KeyboardInput>()
+///         .watch_resource::<MyState>(),
+/// );
+/// app.add_systems(
+///     Update,
+///     build_ui
+///         .after(ImmIdleSystems::Decide)
+///         .run_if(imm_rebuild_needed::<CapsUi>),
+/// );
+/// ```
+pub struct ImmIdlePlugin<Caps> {
+    watchers: Vec<fn(&mut bevy_app::App, InternedScheduleLabel)>,
+    awake_frames: u32,
+    _ph: PhantomData<Caps>,
+}
+
+impl<Caps: Send + Sync + 'static> ImmIdlePlugin<Caps> {
+    /// Construct plugin
+    pub fn new() -> Self {
+        Self {
+            watchers: Vec::new(),
+            awake_frames: 2,
+            _ph: PhantomData,
+        }
+    }
+
+    /// Rebuild entity tree when resource changes
+    pub fn watch_resource<R: Resource>(mut self) -> Self {
+        self.watchers.push(|app, schedule| {
+            app.add_systems(
+                schedule,
+                watch_resource::<Caps, R>.in_set(ImmIdleSystems::Watch),
+            );
+        });
+        self
+    }
+
+    /// Rebuild entity tree when message is sent
+    pub fn watch_message<M: Message>(mut self) -> Self {
+        self.watchers.push(|app, schedule| {
+            app.add_systems(
+                schedule,
+                watch_message::<Caps, M>.in_set(ImmIdleSystems::Watch),
+            );
+        });
+        self
+    }
+
+    /// Amount of frames that are rebuilt after activity is detected. Default: 2
+    ///
+    /// Layout changes may need additional frames to be reflected in entity tree.
+    pub fn awake_frames(mut self, frames: u32) -> Self {
+        self.awake_frames = frames.max(1);
+        self
+    }
+}
+
+impl<Caps: Send + Sync + 'static> Default for ImmIdlePlugin<Caps> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Caps: Send + Sync + 'static> bevy_app::Plugin for ImmIdlePlugin<Caps> {
+    fn build(&self, app: &mut bevy_app::App) {
+        let schedule = app
+            .world()
+            .get_resource::<crate::ImmSchedules<Caps>>()
+            .expect("BevyImmediatePlugin must be added before ImmIdlePlugin")
+            .build();
+
+        app.insert_resource(IdleState::<Caps> {
+            awake_frames: self.awake_frames,
+            // First frames always build entity tree
+            awake_frames_left: self.awake_frames,
+            _ph: PhantomData,
+        });
+        app.configure_sets(
+            schedule,
+            ImmIdleSystems::Watch.before(ImmIdleSystems::Decide),
+        );
+        app.add_systems(
+            schedule,
+            decide_frame_skip::<Caps>.in_set(ImmIdleSystems::Decide),
+        );
+
+        for watcher in self.watchers.iter() {
+            watcher(app, schedule);
+        }
+    }
+}
+
+/// System sets used by [`ImmIdlePlugin`] in build schedule ([`crate::ImmSchedules::build`])
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ImmIdleSystems {
+    /// Detect activity
+    Watch,
+    /// Decide if current frame is skipped
+    Decide,
+}
+
+#[derive(Resource)]
+struct IdleState<Caps> {
+    awake_frames: u32,
+    awake_frames_left: u32,
+    _ph: PhantomData<Caps>,
+}
+
+fn watch_resource<Caps: Send + Sync + 'static, R: Resource>(
+    resource: Res<R>,
+    mut skip: ResMut<ImmFrameSkip<Caps>>,
+) {
+    if resource.is_changed() {
+        skip.rebuild_requested = true;
+    }
+}
+
+fn watch_message<Caps: Send + Sync + 'static, M: Message>(
+    mut messages: MessageReader<M>,
+    mut skip: ResMut<ImmFrameSkip<Caps>>,
+) {
+    if !messages.is_empty() {
+        messages.clear();
+        skip.rebuild_requested = true;
+    }
+}
+
+fn decide_frame_skip<Caps: Send + Sync + 'static>(
+    mut skip: ResMut<ImmFrameSkip<Caps>>,
+    mut state: ResMut<IdleState<Caps>>,
+) {
+    if std::mem::take(&mut skip.rebuild_requested) {
+        state.awake_frames_left = state.awake_frames;
+    }
+
+    if state.awake_frames_left > 0 {
+        state.awake_frames_left -= 1;
+    } else {
+        skip.skipped = true;
+    }
+}
@@ -0,0 +1,190 @@
+use std::marker::PhantomData;
+
+use bevy_ecs::{
+    change_detection::Tick,
+    entity::Entity,
+    resource::Resource,
+    system::{Commands, SystemState},
+    world::World,
+};
+
+use crate::{CapSet, Imm, ImmCtx};
+
+/// Builds immediate mode entity trees with exclusive [`World`] access
+///
+/// Usable from exclusive systems and, through [`Self::queue_build_root`], from observers.
+///
+/// Entity tree is built with the same [`ImmCtx`] as in regular systems, so all capabilities
+/// work unchanged. Build closure is executed once. Commands are applied to the world as soon as
+/// entity is spawned and after each `on_spawn_*` hook
+/// ([`crate::ImmEntity::at_this_moment_apply_commands`]), so capabilities can read and write
+/// components of freshly spawned entities during the same build.
+/// Remaining commands are applied right after build closure returns.
+///
+/// ```ignore
+/// fn build_ui(world: &mut World) {
+///     ImmWorld::<CapsUi>::new(world).build_immediate_root("menu", |ui| {
+///         ui.ch().on_spawn_insert(|| Node::default());
+///     });
+/// }
+/// ```
+pub struct ImmWorld<'a, Caps: CapSet> {
+    world: &'a mut World,
+    _ph: PhantomData<Caps>,
+}
+
+impl<'a, Caps: CapSet> ImmWorld<'a, Caps> {
+    /// Construct builder. [`crate::BevyImmediatePlugin<Caps>`] must be added to app
+    pub fn new(world: &'a mut World) -> Self {
+        Self {
+            world,
+            _ph: PhantomData,
+        }
+    }
+
+    /// Access underlying world
+    pub fn world_mut(&mut self) -> &mut World {
+        self.world
+    }
+
+    /// Build entity hierarchy managed by immediate mode
+    pub fn build_immediate_root<T: std::hash::Hash, R>(
+        &mut self,
+        root_id: T,
+        f: impl FnOnce(&mut Imm<'_, '_, Caps>) -> R,
+    ) -> R {
+        self.build(|ctx| ctx.build_immediate_root(root_id), f)
+    }
+
+    /// Build entity hierarchy managed by immediate mode that targets given render target
+    ///
+    /// See [`ImmCtx::build_immediate_root_for`].
+    pub fn build_immediate_root_for<T: std::hash::Hash, R>(
+        &mut self,
+        root_id: T,
+        target: Entity,
+        f: impl FnOnce(&mut Imm<'_, '_, Caps>) -> R,
+    ) -> R {
+        self.build(|ctx| ctx.build_immediate_root_for(root_id, target), f)
+    }
+
+    /// Build entity hierarchy managed by immediate mode starting from given **existing** entity
+    pub fn build_immediate_from<T: std::hash::Hash, R>(
+        &mut self,
+        root_id: T,
+        entity: Entity,
+        f: impl FnOnce(&mut Imm<'_, '_, Caps>) -> R,
+    ) -> R {
+        self.build(|ctx| ctx.build_immediate_from(root_id, entity), f)
+    }
+
+    /// Queue entity hierarchy build that is executed when commands are applied
+    ///
+    /// Useful inside observers and other systems without exclusive world access.
+    pub fn queue_build_root<T>(
+        commands: &mut Commands,
+        root_id: T,
+        f: impl FnOnce(&mut Imm<'_, '_, Caps>) + Send + 'static,
+    ) where
+        T: std::hash::Hash + Send + 'static,
+    {
+        commands.queue(move |world: &mut World| {
+            ImmWorld::<Caps>::new(world).build_immediate_root(root_id, f);
+        });
+    }
+
+    fn build<R>(
+        &mut self,
+        root: impl for<'w, 's> FnOnce(ImmCtx<'w, 's, Caps>) -> Imm<'w, 's, Caps>,
+        f: impl FnOnce(&mut Imm<'_, '_, Caps>) -> R,
+    ) -> R {
+        // States are taken out of world to support nested builds started while commands
+        // are applied. Each nesting level reuses its own state.
+        let mut state = self
+            .world
+            .get_resource_mut::<ImmWorldStates<Caps>>()
+            .and_then(|mut states| states.0.pop())
+            .unwrap_or_else(|| SystemState::new(self.world));
+
+        let world: *mut World = self.world;
+        let state_ptr: *mut ImmWorldState<Caps> = &mut state;
+        let last_run = state.meta().get_last_run();
+
+        let value = {
+            // SAFETY: State was created for this world. World and state outlive the build and
+            // are accessed only through these pointers until build closure returns.
+            let ctx = unsafe { (*state_ptr).get_unchecked((*world).as_unsafe_world_cell()) }
+                .expect("bevy_immediate system params are not valid");
+            let mut imm = root(ctx);
+            imm.world = Some(WorldAccess {
+                world,
+                state: state_ptr,
+                last_run,
+            });
+            f(&mut imm)
+        };
+        state.apply(self.world);
+
+        self.world
+            .get_resource_or_insert_with(ImmWorldStates::<Caps>::default)
+            .0
+            .push(state);
+
+        value
+    }
+}
+
+type ImmWorldState<Caps> = SystemState<ImmCtx<'static, 'static, Caps>>;
+
+/// Cached system states of [`ImmWorld`] builds
+#[derive(Resource)]
+struct ImmWorldStates<Caps: CapSet>(Vec<ImmWorldState<Caps>>);
+
+impl<Caps: CapSet> Default for ImmWorldStates<Caps> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+/// Exclusive world access of entity tree build started by [`ImmWorld`]
+pub(super) struct WorldAccess<Caps: CapSet> {
+    world: *mut World,
+    state: *mut ImmWorldState<Caps>,
+    /// Last run tick of system state before build started
+    last_run: Tick,
+}
+
+impl<Caps: CapSet> Clone for WorldAccess<Caps> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Caps: CapSet> Copy for WorldAccess<Caps> {}
+
+impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
+    /// Apply queued commands to world when entity tree is built by [`ImmWorld`]
+    ///
+    /// System params are fetched again, so that capabilities can access freshly spawned entities.
+    pub(super) fn apply_world_commands(&mut self) {
+        let Some(access) = self.world else {
+            return;
+        };
+
+        // SAFETY: World and state outlive the build (see `ImmWorld::build`).
+        // `&mut self` guarantees that no data borrowed from current params is alive.
+        // Current params are overwritten without being used or dropped after world is mutated.
+        unsafe {
+            let state = &mut *access.state;
+            let world = &mut *access.world;
+            state.apply(world);
+
+            // Change detection keeps comparing against ticks from before the build
+            state.meta_mut().set_last_run(access.last_run);
+            let ctx = state
+                .get_unchecked(world.as_unsafe_world_cell())
+                .expect("bevy_immediate system params are not valid");
+            std::ptr::write((&raw mut self.ctx).cast::<ImmCtx<'_, '_, Caps>>(), ctx);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::marker::PhantomData;
+
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    query::With,
+    schedule::{InternedScheduleLabel, IntoScheduleConfigs},
+    system::{Commands, Query},
+};
+
+use crate::{CapSet, ImmediateSystemSet};
+
+pub(super) fn init<Caps: CapSet>(app: &mut bevy_app::App, schedule: InternedScheduleLabel) {
+    app.add_systems(
+        schedule,
+        leaving_entity_upkeep_system::<Caps>
+            .in_set(ImmediateSystemSet::<Caps>::default())
+            .after(super::upkeep::immediate_mode_tracked_entity_upkeep_system::<Caps>),
+    );
+}
+
+/// Opt-in leaving phase for immediate mode entity
+///
+/// When entity with this component is not built during frame, instead of being despawned
+/// it receives [`ImmLeaving`] component and stays alive until [`ImmLeaving::finish`] is called
+/// or `timeout_frames` have passed. Useful to implement exit transitions.
+///
+/// Leaving entity and its descendants are no longer managed by immediate mode.
+/// If entity with the same id is built again, new entity is spawned.
+///
+/// Leaving entity stays child of its parent, so that it keeps its place in layout.
+/// If parent is despawned (for example, it is not built either), leaving entity is despawned
+/// together with it and exit transition is interrupted. Add [`ImmDeferredDespawn`] to the
+/// topmost entity that should transition out.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ImmDeferredDespawn {
+    /// Amount of frames after which leaving entity is despawned even if it was not finished
+    pub timeout_frames: u32,
+}
+
+/// Marks entity that is not built anymore and is in leaving phase. See [`ImmDeferredDespawn`]
+///
+/// Leaving phase starts in the same frame in which entity was not built.
+/// [`Self::frames`] is incremented at the end of each frame by upkeep systems
+/// ([`ImmediateSystemSet`]).
+#[derive(Component, Default, Debug)]
+pub struct ImmLeaving {
+    frames: u32,
+    finished: bool,
+}
+
+impl ImmLeaving {
+    /// Signal that leaving phase is completed. Entity will be despawned.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Has leaving phase been completed
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Amount of frames since entity started leaving
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+}
+
+/// Capability set that manages leaving entity
+#[derive(Component)]
+pub(super) struct ImmLeavingOwner<Caps>(PhantomData<Caps>);
+
+impl<Caps> Default for ImmLeavingOwner<Caps> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn leaving_entity_upkeep_system<Caps: CapSet>(
+    query: Query<
+        (Entity, &mut ImmLeaving, Option<&ImmDeferredDespawn>),
+        With<ImmLeavingOwner<Caps>>,
+    >,
+    mut commands: Commands,
+) {
+    for (entity, mut leaving, deferred) in query {
+        let timeout = deferred.map(|d| d.timeout_frames).unwrap_or(0);
+        if leaving.finished || leaving.frames >= timeout {
+            commands.entity(entity).try_despawn();
+            continue;
+        }
+        leaving.frames += 1;
+    }
+}
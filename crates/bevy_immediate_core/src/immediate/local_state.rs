@@ -0,0 +1,117 @@
+use std::{
+    any::{Any, TypeId},
+    marker::PhantomData,
+};
+
+use bevy_ecs::{
+    entity::Entity,
+    resource::Resource,
+    schedule::{InternedScheduleLabel, IntoScheduleConfigs},
+    system::{Query, ResMut},
+};
+use bevy_platform::collections::{HashMap, HashSet};
+
+use crate::{ImmId, ImmediateSystemSet, imm_rebuild_needed};
+
+pub fn init<Caps: Send + Sync + 'static>(app: &mut bevy_app::App, schedule: InternedScheduleLabel) {
+    app.add_systems(
+        schedule,
+        clean_local_state::<Caps>
+            .in_set(ImmediateSystemSet::<Caps>::default())
+            .run_if(imm_rebuild_needed::<Caps>),
+    );
+    app.insert_resource(LocalState::<Caps>::default());
+}
+
+/// Stores typed state for immediate mode entities
+///
+/// State is dropped when entity is despawned.
+#[derive(Resource)]
+pub(super) struct LocalState<Caps> {
+    values: HashMap<Key, Box<dyn Any + Send + Sync>>,
+    _ph: PhantomData<Caps>,
+}
+
+impl<Caps> LocalState<Caps> {
+    /// Remove all values stored for provided entities
+    pub fn remove_entities(&mut self, entities: &HashSet<Entity>) {
+        if entities.is_empty() {
+            return;
+        }
+        self.values
+            .retain(|key, _value| !entities.contains(&key.entity));
+    }
+
+    pub fn get_or_insert_with<T: Send + Sync + 'static>(
+        &mut self,
+        entity: Entity,
+        key: Option<ImmId>,
+        default: impl FnOnce() -> T,
+    ) -> &mut T {
+        let key = Key {
+            entity,
+            type_id: TypeId::of::<T>(),
+            key,
+        };
+
+        self.values
+            .entry(key)
+            .or_insert_with(|| Box::new(default()))
+            .downcast_mut::<T>()
+            .expect("Local state is stored by its TypeId")
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self, entity: Entity, key: Option<ImmId>) -> Option<&T> {
+        let key = Key {
+            entity,
+            type_id: TypeId::of::<T>(),
+            key,
+        };
+
+        self.values
+            .get(&key)
+            .and_then(|value| value.downcast_ref::<T>())
+    }
+
+    pub fn remove<T: Send + Sync + 'static>(
+        &mut self,
+        entity: Entity,
+        key: Option<ImmId>,
+    ) -> Option<T> {
+        let key = Key {
+            entity,
+            type_id: TypeId::of::<T>(),
+            key,
+        };
+
+        self.values
+            .remove(&key)
+            .and_then(|value| value.downcast::<T>().ok())
+            .map(|value| *value)
+    }
+}
+
+impl<Caps> Default for LocalState<Caps> {
+    fn default() -> Self {
+        Self {
+            values: Default::default(),
+            _ph: Default::default(),
+        }
+    }
+}
+
+#[derive(Hash, PartialEq, Eq)]
+struct Key {
+    entity: Entity,
+    type_id: TypeId,
+    key: Option<ImmId>,
+}
+
+fn clean_local_state<Caps: Send + Sync + 'static>(
+    mut state: ResMut<LocalState<Caps>>,
+    query: Query<()>,
+) {
+    state
+        .values
+        .retain(|key, _value| query.contains(key.entity));
+}
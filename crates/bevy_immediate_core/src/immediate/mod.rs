@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, sync::Arc};
+use std::marker::PhantomData;
 
 use crate::{CapSet, ImmCapAccessRequests, ImmCapAccessRequestsResource};
 use bevy_ecs::{
@@ -7,22 +7,64 @@ use bevy_ecs::{
     component::{Component, Mutable},
     entity::Entity,
     event::EntityEvent,
-    hierarchy::ChildOf,
-    query::{QueryEntityError, Without},
+    hierarchy::{ChildOf, Children},
+    query::{QueryData, QueryEntityError, QueryFilter, ROQueryItem, Without},
     resource::Resource,
-    system::{Commands, EntityCommands, IntoObserverSystem, Query},
+    schedule::{InternedScheduleLabel, ScheduleLabel},
+    system::{Commands, EntityCommands, IntoObserverSystem, Query, Res},
     world::{FilteredEntityRef, Mut, error::ResourceFetchError},
 };
 
 /// Plugin for immediate mode functionality in bevy
 ///
 /// Can be initialized multiple times without problems
-pub struct BevyImmediatePlugin<Caps = ()>(PhantomData<Caps>);
+///
+/// By default entity trees are expected to be built in [`bevy_app::Update`] and
+/// upkeep systems ([`ImmediateSystemSet`]) run in [`bevy_app::PostUpdate`].
+/// Use [`Self::with_build_schedule`] and [`Self::with_upkeep_schedule`] to change them.
+/// Selected schedules are available through [`ImmSchedules<Caps>`] resource.
+pub struct BevyImmediatePlugin<Caps = ()> {
+    build_schedule: InternedScheduleLabel,
+    upkeep_schedule: InternedScheduleLabel,
+    auto_id_mode: ImmAutoIdMode,
+    _ph: PhantomData<Caps>,
+}
 
 impl<Caps> BevyImmediatePlugin<Caps> {
     /// Construct plugin
     pub fn new() -> Self {
-        Self(PhantomData)
+        Self {
+            build_schedule: bevy_app::Update.intern(),
+            upkeep_schedule: bevy_app::PostUpdate.intern(),
+            auto_id_mode: ImmAutoIdMode::Index,
+            _ph: PhantomData,
+        }
+    }
+
+    /// Schedule in which entity trees are built
+    ///
+    /// Used by plugins that build entity trees, like `BevyImmediateAttachPlugin`.
+    pub fn with_build_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.build_schedule = schedule.intern();
+        self
+    }
+
+    /// Schedule in which entities that were not built are removed and caches are cleaned
+    ///
+    /// Must run after build schedule in the same frame. For example, use
+    /// [`bevy_app::FixedPostUpdate`] when entity tree is built in [`bevy_app::FixedUpdate`].
+    /// UI entity trees must be upkept before layout is computed, in or before [`bevy_app::PostUpdate`].
+    pub fn with_upkeep_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.upkeep_schedule = schedule.intern();
+        self
+    }
+
+    /// How `.ch()` generates ids. Default: [`ImmAutoIdMode::Index`]
+    ///
+    /// Available through [`ImmAutoIdConfig<Caps>`] resource.
+    pub fn with_auto_id_mode(mut self, mode: ImmAutoIdMode) -> Self {
+        self.auto_id_mode = mode;
+        self
     }
 }
 
@@ -41,16 +83,36 @@ where
             return;
         }
 
+        app.insert_resource(ImmSchedules::<Caps> {
+            build: self.build_schedule,
+            upkeep: self.upkeep_schedule,
+            _ph: PhantomData,
+        });
+        app.insert_resource(ImmAutoIdConfig::<Caps> {
+            mode: self.auto_id_mode,
+            _ph: PhantomData,
+        });
+
+        let upkeep = self.upkeep_schedule;
         entity_mapping::init::<Caps>(app);
-        upkeep::init::<Caps>(app);
-        cached_hash::init::<Caps>(app);
+        id_collision::init::<Caps>(app, upkeep);
+        #[cfg(debug_assertions)]
+        debug_path::init::<Caps>(app, upkeep);
+        upkeep::init::<Caps>(app, upkeep);
+        cached_hash::init::<Caps>(app, upkeep);
+        local_state::init::<Caps>(app, upkeep);
+        detached::init::<Caps>(app, upkeep);
+        pool::init::<Caps>(app);
+        frame_stats::init::<Caps>(app, upkeep);
+        idle::init::<Caps>(app, upkeep);
+        leaving::init::<Caps>(app, upkeep);
 
         let mut capabilities = ImmCapAccessRequests::<Caps>::default();
         Caps::initialize(app, &mut capabilities);
         app.insert_resource(ImmCapAccessRequestsResource::new(capabilities));
 
         #[cfg(feature = "hotpatching")]
-        hotpatching::init(app);
+        hotpatching::init::<Caps>(app);
     }
 
     fn is_unique(&self) -> bool {
@@ -63,20 +125,63 @@ where
 /// Adds support for bevy inbuilt hotpatching mechanism
 #[cfg(feature = "hotpatching")]
 pub mod hotpatching;
+#[cfg(feature = "hotpatching")]
+pub use hotpatching::ImmHotpatchMode;
 
 mod system_set;
-pub use system_set::ImmediateSystemSet;
+pub use system_set::{ImmSchedules, ImmediateSystemSet};
 
 mod ctx;
 pub use ctx::ImmCtx;
 
 mod id;
 use crate::utils::ImmTypeMap;
-pub use id::{ImmId, ImmIdBuilder, imm_id};
+pub use id::{ImmAutoIdConfig, ImmAutoIdMode, ImmId, ImmIdBuilder, imm_id};
 
 mod cached_hash;
+mod detached;
 mod entity_mapping;
+
+mod id_collision;
+pub use id_collision::{ImmCallSite, ImmDebugLabel, ImmIdCollision, ImmIdCollisions};
+mod diagnostics;
+pub use diagnostics::{
+    ImmDiagnostic, ImmDiagnosticChecks, ImmDiagnosticKind, ImmDiagnostics, ImmDiagnosticsMode,
+    ImmDiagnosticsPlugin,
+};
+#[cfg(debug_assertions)]
+mod debug_path;
+#[cfg(debug_assertions)]
+pub use debug_path::ImmDebugPath;
+mod leaving;
+pub use leaving::{ImmDeferredDespawn, ImmLeaving};
+mod frame_stats;
+mod idle;
+pub use idle::{ImmFrameSkip, ImmIdlePlugin, ImmIdleSystems, imm_rebuild_needed};
+mod imm_world;
+pub use imm_world::ImmWorld;
+mod local_state;
+pub use frame_stats::{ImmFrameCounters, ImmFrameStats};
+mod pool;
+pub use pool::{ImmEntityPool, ImmPoolStats, ImmPooled};
+
 mod upkeep;
+pub use upkeep::{ImmRetained, ImmRetention};
+
+/// Headless test harness for immediate mode entity trees
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(test)]
+mod tests;
+
+/// Serializable snapshots of immediate mode entity trees
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+#[cfg(feature = "snapshot")]
+pub use snapshot::{
+    ImmSnapshot, ImmSnapshotChange, ImmSnapshotConfig, ImmSnapshotDiff, ImmSnapshotError,
+    ImmSnapshotNode,
+};
 
 /// Helper type to more easily write queries
 pub type ImmQuery<'w, 's, Caps, D, F = ()> = Query<'w, 's, D, (Without<ImmMarker<Caps>>, F)>;
@@ -93,6 +198,8 @@ pub type ImmQuery<'w, 's, Caps, D, F = ()> = Query<'w, 's, D, (Without<ImmMarker
 pub struct Imm<'w, 's, Caps: CapSet> {
     ctx: ImmCtx<'w, 's, Caps>,
     current: Current,
+    /// Set when entity tree is built by [`ImmWorld`]
+    world: Option<imm_world::WorldAccess<Caps>>,
 }
 
 #[derive(Clone, Copy)]
@@ -101,6 +208,12 @@ struct Current {
     entity: Option<CurrentEntity>,
     id_pref: ImmId,
     auto_id_idx: usize,
+    root_target: Option<Entity>,
+    auto_id_mode: ImmAutoIdMode,
+    /// Entities built in this scope are recorded as detached roots of given owner id
+    detached: Option<ImmId>,
+    /// Nesting depth of entities built in this scope, index of their temporary store
+    depth: usize,
 }
 
 #[derive(Clone, Copy)]
@@ -115,6 +228,7 @@ impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
     /// Use [`Self::ch_id`] if building entities that may not always exist when parent entity exists.
     ///
     /// Read more [`ImmId`], [`ImmIdBuilder`].
+    #[track_caller]
     pub fn ch(&mut self) -> ImmEntity<'_, 'w, 's, Caps> {
         self.ch_with_manual_id(ImmIdBuilder::Auto)
     }
@@ -125,15 +239,92 @@ impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
     /// Ids must be unique between sibling entities.
     ///
     /// Read more [`ImmId`], [`ImmIdBuilder`].
+    #[track_caller]
     pub fn ch_id<T: std::hash::Hash>(&mut self, id: T) -> ImmEntity<'_, 'w, 's, Caps> {
+        #[cfg(debug_assertions)]
+        self.ctx.debug_paths.set_source(&id);
         self.ch_with_manual_id(ImmIdBuilder::Hierarchy(ImmId::new(id)))
     }
 
     /// Build new entity with provided id.
     ///
     /// Read more [`ImmId`], [`ImmIdBuilder`].
+    #[track_caller]
     pub fn ch_with_manual_id(&mut self, id: ImmIdBuilder) -> ImmEntity<'_, 'w, 's, Caps> {
+        self.ch_inner(id, None)
+    }
+
+    /// Build new entity with auto generated id that is reused from [`ImmEntityPool`]
+    /// when possible.
+    ///
+    /// Entities with the same `kind` must be built with the same set of components.
+    /// Read more [`ImmEntityPool`].
+    #[track_caller]
+    pub fn ch_pooled<K: std::hash::Hash>(&mut self, kind: K) -> ImmEntity<'_, 'w, 's, Caps> {
+        self.ch_inner(ImmIdBuilder::Auto, Some(ImmId::new(kind)))
+    }
+
+    /// Build new entity with manually provided id that is reused from [`ImmEntityPool`]
+    /// when possible.
+    ///
+    /// Entities with the same `kind` must be built with the same set of components.
+    /// Read more [`ImmEntityPool`].
+    #[track_caller]
+    pub fn ch_id_pooled<T: std::hash::Hash, K: std::hash::Hash>(
+        &mut self,
+        id: T,
+        kind: K,
+    ) -> ImmEntity<'_, 'w, 's, Caps> {
+        #[cfg(debug_assertions)]
+        self.ctx.debug_paths.set_source(&id);
+        self.ch_inner(
+            ImmIdBuilder::Hierarchy(ImmId::new(id)),
+            Some(ImmId::new(kind)),
+        )
+    }
+
+    /// Build new entity with provided id that is reused from [`ImmEntityPool`]
+    /// when possible.
+    ///
+    /// Read more [`ImmEntityPool`].
+    #[track_caller]
+    pub fn ch_with_manual_id_pooled<K: std::hash::Hash>(
+        &mut self,
+        id: ImmIdBuilder,
+        kind: K,
+    ) -> ImmEntity<'_, 'w, 's, Caps> {
+        self.ch_inner(id, Some(ImmId::new(kind)))
+    }
+
+    #[track_caller]
+    fn ch_inner(
+        &mut self,
+        id: ImmIdBuilder,
+        pool_kind: Option<ImmId>,
+    ) -> ImmEntity<'_, 'w, 's, Caps> {
+        #[cfg(debug_assertions)]
+        let location = std::panic::Location::caller();
+        #[cfg(debug_assertions)]
+        let id_source = match id {
+            ImmIdBuilder::Auto if self.current.auto_id_mode == ImmAutoIdMode::CallSite => {
+                let occurrence = self
+                    .ctx
+                    .call_sites
+                    .get(&(self.current.id, self.current.id_pref, location))
+                    .copied()
+                    .unwrap_or(0);
+                debug_path::IdSource::CallSite(location, occurrence)
+            }
+            ImmIdBuilder::Auto => debug_path::IdSource::Auto(self.current.auto_id_idx),
+            _ => debug_path::IdSource::Manual,
+        };
+
         let id = id.resolve(self);
+        #[cfg(debug_assertions)]
+        let debug_path =
+            self.ctx
+                .debug_paths
+                .child(self.current.id, self.current.id_pref, id, id_source);
 
         let mut will_be_spawned = false;
 
@@ -150,8 +341,23 @@ impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
                 // Update iteration for entity upkeep tracking
                 qentity.tracker.iteration = self.ctx.state.iteration;
 
+                #[cfg(debug_assertions)]
+                {
+                    qentity.tracker.location = location;
+                }
+
+                if qentity.retained {
+                    // Entity is built again
+                    self.ctx.commands.entity(entity).remove::<ImmRetained>();
+                }
+
                 if qentity.child_of.map(|ch| ch.parent()) != self.current.entity.map(|e| e.entity) {
                     // Parent changed
+                    self.ctx.frame_stats.current_mut().reparented += 1;
+                    #[cfg(debug_assertions)]
+                    if let Some(diagnostics) = self.ctx.diagnostics.as_deref_mut() {
+                        diagnostics.reparented(location, entity, &debug_path);
+                    }
                     let mut entity_commands = self.ctx.commands.entity(entity);
                     match self.current.entity {
                         Some(entity) => {
@@ -166,26 +372,85 @@ impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
                 break 'entity_retrieval entity;
             }
 
+            // Add marker component that users can use in QueryFilter Without statements
+            let marker = ImmMarker::<Caps> {
+                id,
+                iteration: self.ctx.state.iteration,
+                #[cfg(debug_assertions)]
+                location,
+                _ph: PhantomData,
+            };
+
+            let pooled_entity =
+                pool_kind.and_then(|kind| self.ctx.pool.take(kind, &self.ctx.pooled_query));
+
             // Spawn entity by default if valid entity not found
-            let mut commands = self.ctx.commands.spawn((
-                // Add marker component that users can use in QueryFilter Without statements
-                ImmMarker::<Caps> {
-                    id,
-                    iteration: self.ctx.state.iteration,
-                    _ph: PhantomData,
-                },
-            ));
+            let mut commands = match pooled_entity {
+                Some(entity) => {
+                    let mut commands = self.ctx.commands.entity(entity);
+                    commands
+                        .remove_recursive::<Children, bevy_ecs::entity_disabling::Disabled>()
+                        .remove::<ImmPooled>()
+                        .insert(marker);
+                    commands
+                }
+                None => {
+                    self.ctx.frame_stats.current_mut().spawned += 1;
+                    let mut commands = self.ctx.commands.spawn(marker);
+                    if let Some(kind) = pool_kind {
+                        commands.insert(pool::ImmPoolKind(kind));
+                    }
+                    commands
+                }
+            };
 
             if let Some(entity) = self.current.entity {
                 commands.insert(ChildOf(entity.entity));
             }
+            #[cfg(debug_assertions)]
+            commands.insert(debug_path::component(debug_path.clone()));
             will_be_spawned = true;
             commands.id()
         };
 
-        // TODO: Avoid clone
-        let access_requests: Arc<_> = self.ctx.access_requests.capabilities.clone();
+        self.ctx.frame_stats.current_mut().visited += 1;
+
+        if let Some(owner) = self.current.detached {
+            self.ctx
+                .detached
+                .record(owner, entity, self.ctx.state.iteration);
+        }
 
+        #[cfg(debug_assertions)]
+        if let debug_path::IdSource::Auto(_) | debug_path::IdSource::CallSite(..) = id_source
+            && let Some(diagnostics) = self.ctx.diagnostics.as_deref_mut()
+        {
+            diagnostics.auto_id(
+                self.current.id,
+                self.current.id_pref,
+                id,
+                location,
+                entity,
+                &debug_path,
+            );
+        }
+
+        #[cfg(debug_assertions)]
+        let debug_visit = Some(
+            self.ctx
+                .id_collisions
+                .visit(id, entity, location, debug_path),
+        );
+
+        if will_be_spawned {
+            // Entity becomes accessible to capabilities in builds with exclusive world access
+            self.apply_world_commands();
+        }
+
+        // Resource reference outlives borrow of `self`, listeners are called without refcounting
+        let access_requests = Res::clone(&self.ctx.access_requests).into_inner();
+
+        let tmp_store = self.ctx.tmp_stores.acquire(self.current.depth);
         let mut entity = ImmEntity {
             imm: self,
             e: EntityParams {
@@ -193,16 +458,68 @@ impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
                 entity,
                 will_be_spawned,
             },
-            tmp_store: ImmTypeMap::new(),
+            tmp_store,
+            #[cfg(debug_assertions)]
+            debug_visit,
+            rerun_on_hotpatch: false,
         };
 
-        for on_children in access_requests.on_children.iter() {
+        for on_children in access_requests.capabilities.on_children.iter() {
             (on_children)(&mut entity);
         }
 
         entity
     }
 
+    /// Mark immediate mode entities that were built under `entity` with id `id`
+    /// in previous frames as built during this frame
+    ///
+    /// Includes descendants of entity and entity trees built with [`Self::unrooted`]
+    /// and [`Self::portal`] from its subtree.
+    fn keep_built_alive(&mut self, entity: Option<Entity>, id: ImmId) {
+        let iteration = self.ctx.state.iteration;
+
+        let mut queue = std::mem::take(&mut *self.ctx.keep_alive);
+        queue.clear();
+        if let Some(entity) = entity {
+            queue.extend(self.ctx.children_query.iter_descendants::<Children>(entity));
+        }
+        queue.extend(self.ctx.detached.get(id));
+
+        let mut idx = 0;
+        while let Some(&entity) = queue.get(idx) {
+            idx += 1;
+
+            let Ok(mut qentity) = self.ctx.entity_query.get_mut(entity) else {
+                continue;
+            };
+            // Retained entities were not built during previous frame either
+            if !qentity.retained {
+                qentity.tracker.iteration = iteration;
+                // Retention policy of entity that is not rebuilt stays in effect
+                if qentity.has_retention {
+                    self.ctx.state.retention_set.insert(entity);
+                }
+            }
+            let id = qentity.tracker.id;
+            self.ctx.frame_stats.current_mut().kept += 1;
+
+            #[cfg(debug_assertions)]
+            {
+                let location = qentity.tracker.location;
+                let path = self.ctx.debug_paths.get(id).cloned().unwrap_or_default();
+                self.ctx.id_collisions.visit(id, entity, location, path);
+            }
+
+            for root in self.ctx.detached.get(id) {
+                queue.push(root);
+                queue.extend(self.ctx.children_query.iter_descendants::<Children>(root));
+            }
+        }
+
+        *self.ctx.keep_alive = queue;
+    }
+
     /// Add additional id to final id generation
     ///
     /// Useful in situations where multiple elements require unique id.
@@ -213,6 +530,43 @@ impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
         ImmScopeGuard::add_id_pref(self, id)
     }
 
+    /// Build entities for each item with ids derived from item key
+    ///
+    /// Entities built inside closure (with `.ch()` or `.ch_id()`) keep their identity
+    /// when items are inserted, removed or reordered. Keys must be unique between items.
+    pub fn for_each_keyed<I, K, T>(
+        &mut self,
+        iter: I,
+        key_fn: impl Fn(&T) -> K,
+        mut f: impl FnMut(&mut Imm<'w, 's, Caps>, T),
+    ) where
+        I: IntoIterator<Item = T>,
+        K: std::hash::Hash,
+    {
+        for item in iter {
+            let key = key_fn(&item);
+            let mut ui = self.with_add_id_pref(key);
+            f(&mut ui, item);
+        }
+    }
+
+    /// Build entities for each entity matched by query with ids derived from [`Entity`]
+    ///
+    /// See [`Self::for_each_keyed`].
+    pub fn for_each_query<'q, D, F>(
+        &mut self,
+        query: &'q Query<'_, '_, (Entity, D), F>,
+        mut f: impl FnMut(&mut Imm<'w, 's, Caps>, Entity, ROQueryItem<'q, '_, D>),
+    ) where
+        D: QueryData,
+        F: QueryFilter,
+    {
+        for (entity, item) in query.iter() {
+            let mut ui = self.with_add_id_pref(entity);
+            f(&mut ui, entity, item);
+        }
+    }
+
     /// Add additional id to final id generation
     ///
     /// Useful in situations where multiple elements require unique id.
@@ -228,9 +582,21 @@ impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
     ///
     /// In context of UI, useful for tooltips, popups.
     pub fn unrooted<T: std::hash::Hash>(&mut self, id: T, f: impl FnOnce(&mut Imm<'w, 's, Caps>)) {
+        #[cfg(debug_assertions)]
+        self.ctx.debug_paths.set_source(&id);
+
         let id = ImmIdBuilder::Hierarchy(ImmId::new(id)).resolve(self);
 
+        #[cfg(debug_assertions)]
+        self.ctx.debug_paths.child(
+            self.current.id,
+            self.current.id_pref,
+            id,
+            debug_path::IdSource::Manual,
+        );
+
         // Create new unrooted context
+        let owner = self.detached_owner();
         let mut imm = ImmScopeGuard::new_scope(
             self,
             Current {
@@ -238,11 +604,70 @@ impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
                 entity: None,
                 auto_id_idx: 0,
                 id_pref: ImmId::new(49382395483011234u64),
+                root_target: self.current.root_target,
+                auto_id_mode: self.current.auto_id_mode,
+                detached: Some(owner),
+                depth: self.current.depth + 1,
+            },
+        );
+        f(&mut imm);
+    }
+
+    /// Build entity tree under provided existing `target` entity
+    ///
+    /// Ids are generated from current scope and built entities are kept alive only while
+    /// caller keeps building the portal. Entities are despawned once portal is not built anymore.
+    ///
+    /// Portal entities belong to calling entity, so they are kept alive when its subtree
+    /// is not rebuilt with [`ImmEntity::memo`] or [`ImmEntity::keep_children_alive`].
+    ///
+    /// In context of UI, useful for dialogs, drag previews and overlays that must be placed
+    /// under specific entity, e.g. overlay root of window.
+    pub fn portal<T: std::hash::Hash>(
+        &mut self,
+        target: Entity,
+        id: T,
+        f: impl FnOnce(&mut Imm<'w, 's, Caps>),
+    ) {
+        #[cfg(debug_assertions)]
+        self.ctx.debug_paths.set_source(&id);
+
+        let id = ImmIdBuilder::Hierarchy(ImmId::new(id)).resolve(self);
+
+        #[cfg(debug_assertions)]
+        self.ctx.debug_paths.child(
+            self.current.id,
+            self.current.id_pref,
+            id,
+            debug_path::IdSource::Manual,
+        );
+
+        let owner = self.detached_owner();
+        let mut imm = ImmScopeGuard::new_scope(
+            self,
+            Current {
+                id,
+                entity: Some(CurrentEntity {
+                    entity: target,
+                    will_be_spawned: false,
+                }),
+                auto_id_idx: 0,
+                id_pref: ImmId::new(49382395483011234u64),
+                root_target: self.current.root_target,
+                auto_id_mode: self.current.auto_id_mode,
+                detached: Some(owner),
+                depth: self.current.depth + 1,
             },
         );
         f(&mut imm);
     }
 
+    /// Id of entity or scope that owns entity trees built with [`Self::unrooted`]
+    /// and [`Self::portal`] in current scope
+    fn detached_owner(&self) -> ImmId {
+        self.current.detached.unwrap_or(self.current.id)
+    }
+
     /// Entity that is currently being managed
     ///
     /// If building root of entity tree, this value may be [`None`]
@@ -257,6 +682,38 @@ impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
         self.current.id
     }
 
+    /// Render target of entity tree provided to [`ImmCtx::build_immediate_root_for`]
+    ///
+    /// Inherited by unrooted entity trees.
+    #[inline]
+    pub fn root_target(&self) -> Option<Entity> {
+        self.current.root_target
+    }
+
+    /// How `.ch()` generates ids in current scope. See [`ImmAutoIdMode`]
+    #[inline]
+    pub fn auto_id_mode(&self) -> ImmAutoIdMode {
+        self.current.auto_id_mode
+    }
+
+    /// Change how `.ch()` generates ids in current scope and scopes of its descendants
+    ///
+    /// Defaults to [`ImmAutoIdConfig`] of capability set.
+    pub fn set_auto_id_mode(&mut self, mode: ImmAutoIdMode) {
+        self.current.auto_id_mode = mode;
+    }
+
+    /// Number of auto ids previously generated from `location` in current scope
+    fn call_site_occurrence(&mut self, location: &'static std::panic::Location<'static>) -> u32 {
+        let occurrence = self
+            .ctx
+            .call_sites
+            .entry((self.current.id, self.current.id_pref, location))
+            .or_default();
+        *occurrence += 1;
+        *occurrence - 1
+    }
+
     /// Retrieve access to commands
     #[inline]
     pub fn commands_mut(&mut self) -> &mut Commands<'w, 's> {
@@ -302,6 +759,10 @@ impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
                 }),
                 auto_id_idx: 0,
                 id_pref: ImmId::new(49382395483011234u64),
+                root_target: self.current.root_target,
+                auto_id_mode: self.current.auto_id_mode,
+                detached: None,
+                depth: self.current.depth + 1,
             },
         )
     }
@@ -340,17 +801,31 @@ impl<'w, 's, Caps: CapSet> Imm<'w, 's, Caps> {
     /// If parent entity is not managed by Immediate mode, may result in panic
     /// when capabilities try to access data from queries that query only immediate mode entities.
     /// Capabilities will have access to empty temporary store.
+    #[track_caller]
     pub fn reinterpret_as_entity(&mut self) -> Option<ImmEntity<'_, 'w, 's, Caps>> {
         if let Some(current_entity) = self.current.entity {
+            #[cfg(debug_assertions)]
+            if !current_entity.will_be_spawned
+                && !self.ctx.entity_query.contains(current_entity.entity)
+                && let Some(diagnostics) = self.ctx.diagnostics.as_deref()
+            {
+                diagnostics
+                    .non_immediate_entity(std::panic::Location::caller(), current_entity.entity);
+            }
+
             let e = EntityParams {
                 id: self.current.id,
                 entity: current_entity.entity,
                 will_be_spawned: current_entity.will_be_spawned,
             };
+            let tmp_store = self.ctx.tmp_stores.acquire(self.current.depth);
             Some(ImmEntity {
                 imm: self,
                 e,
-                tmp_store: ImmTypeMap::new(),
+                tmp_store,
+                #[cfg(debug_assertions)]
+                debug_visit: None,
+                rerun_on_hotpatch: false,
             })
         } else {
             None
@@ -377,7 +852,12 @@ pub struct ImmEntity<'r, 'w, 's, Caps: CapSet> {
     imm: &'r mut Imm<'w, 's, Caps>,
     /// Entity managed by this instance
     e: EntityParams,
-    tmp_store: ImmTypeMap,
+    /// Nesting depth of temporary store in [`crate::utils::ImmTypeMapStack`]
+    tmp_store: usize,
+    #[cfg(debug_assertions)]
+    debug_visit: Option<id_collision::debug::DebugVisit>,
+    /// See [`Self::rerun_spawn_hooks_on_hotpatch`]
+    rerun_on_hotpatch: bool,
 }
 
 #[derive(Clone, Copy)]
@@ -424,6 +904,18 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
         })
     }
 
+    /// Build entity tree under provided existing `target` entity. See [`Imm::portal`]
+    pub fn portal<T: std::hash::Hash>(
+        self,
+        target: Entity,
+        id: T,
+        f: impl FnOnce(&mut Imm<'w, 's, Caps>),
+    ) -> Self {
+        self.add(|ui| {
+            ui.portal(target, id, f);
+        })
+    }
+
     /// Retrieve system param ctx for immediate mode
     pub fn ctx(&self) -> &ImmCtx<'w, 's, Caps> {
         &self.imm.ctx
@@ -475,13 +967,77 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
         self.e.will_be_spawned
     }
 
+    /// Execute `on_spawn_insert*` and `on_spawn_apply_commands*` functions of this entity again
+    /// during first build after hotpatch when `ImmHotpatchMode::RerunSpawnHooks` is used
+    ///
+    /// Must be called before `on_spawn_*` functions. Opt in only entities whose spawn hooks
+    /// insert style like components, because inserted components replace current ones
+    /// (e.g., text input contents). Scenes reapply only their components, related entities
+    /// of scenes and observers are not spawned again.
+    ///
+    /// Does nothing without `hotpatching` feature.
+    pub fn rerun_spawn_hooks_on_hotpatch(mut self) -> Self {
+        self.rerun_on_hotpatch = true;
+        self
+    }
+
+    /// `on_spawn_*` functions should be executed for this entity
+    ///
+    /// Entity will be spawned or it is opted in with [`Self::rerun_spawn_hooks_on_hotpatch`]
+    /// and `ImmHotpatchMode::RerunSpawnHooks` requests it after hotpatch.
+    #[inline]
+    fn run_spawn_hooks(&self) -> bool {
+        self.e.will_be_spawned || (self.rerun_on_hotpatch && self.imm.ctx.rerun_spawn_hooks())
+    }
+
+    /// Attach label to this entity that is used in diagnostics like [`ImmIdCollisions`]
+    ///
+    /// Label is recorded only in debug builds. It is stored in [`ImmDebugLabel`] component.
+    pub fn debug_label(self, label: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            let label = label.into();
+            let changed = self
+                .imm
+                .ctx
+                .debug_labels
+                .get(self.e.entity)
+                .map_or(true, |current| current.0 != label);
+            if changed {
+                self.imm
+                    .ctx
+                    .commands
+                    .entity(self.e.entity)
+                    .insert(ImmDebugLabel(label.clone()));
+            }
+            if let Some(visit) = self.debug_visit {
+                self.imm.ctx.id_collisions.set_label(visit, label);
+            }
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = label;
+
+        self
+    }
+
+    /// Human readable id path of this entity. See [`ImmDebugPath`]
+    ///
+    /// Available only in debug builds (`debug_assertions`).
+    #[cfg(debug_assertions)]
+    pub fn debug_path(&self) -> Option<&str> {
+        self.imm.ctx.debug_paths.get(self.e.id).map(|path| &**path)
+    }
+
     /// Issue [`EntityCommands`] at this moment
+    ///
+    /// When entity tree is built by [`ImmWorld`], commands are applied to world immediately.
     pub fn at_this_moment_apply_commands<F>(mut self, f: F) -> Self
     where
         F: FnOnce(&mut EntityCommands<'_>),
     {
         let mut entity_commands = self.entity_commands();
         f(&mut entity_commands);
+        self.imm.apply_world_commands();
         self
     }
 
@@ -503,7 +1059,7 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
     where
         F: FnOnce(&mut EntityCommands<'_>),
     {
-        if self.e.will_be_spawned {
+        if self.run_spawn_hooks() {
             self.at_this_moment_apply_commands(f)
         } else {
             self
@@ -516,7 +1072,7 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
     where
         F: FnOnce(&mut EntityCommands<'_>),
     {
-        if self.e.will_be_spawned {
+        if self.run_spawn_hooks() {
             self.at_this_moment_apply_commands_if(f, condition)
         } else {
             self
@@ -525,34 +1081,110 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
 
     /// Apply [`bevy_scene::Scene`] similarly to [`bevy_scene::EntityCommandsSceneExt::apply_scene`].
     /// (scene applied on entity only when entity is created)
+    ///
+    /// When spawn hooks are executed again after hotpatch (see [`Self::rerun_spawn_hooks_on_hotpatch`]),
+    /// only components of the scene are applied again. Related entities of the scene are not respawned.
     #[cfg(feature = "bevy_scene")]
     pub fn on_spawn_apply_scene<F, S>(self, f: F) -> Self
     where
         F: FnOnce() -> S,
         S: bevy_scene::Scene,
     {
-        self.on_spawn_apply_commands(|commands| {
+        if !self.run_spawn_hooks() {
+            return self;
+        }
+        let will_be_spawned = self.e.will_be_spawned;
+        self.at_this_moment_apply_commands(|commands| {
             use bevy_scene::EntityCommandsSceneExt;
 
-            commands.apply_scene(f());
+            if will_be_spawned {
+                commands.apply_scene(f());
+            } else {
+                let scene = f();
+                commands.queue(|entity: bevy_ecs::world::EntityWorldMut<'_>| {
+                    reapply_scene_components(entity, scene);
+                });
+            }
         })
     }
 
     /// Queue apply [`bevy_scene::Scene`] similarly to [`bevy_scene::EntityCommandsSceneExt::queue_apply_scene`].
     /// (scene applied on entity only when entity is created)
+    ///
+    /// When spawn hooks are executed again after hotpatch (see [`Self::rerun_spawn_hooks_on_hotpatch`]),
+    /// only components of the scene are applied again. Scene dependencies are expected
+    /// to be loaded by then, so scene is applied without queueing.
     #[cfg(feature = "bevy_scene")]
     pub fn on_spawn_queue_apply_scene<F, S>(self, f: F) -> Self
     where
         F: FnOnce() -> S,
         S: bevy_scene::Scene,
     {
-        self.on_spawn_apply_commands(|commands| {
+        if !self.run_spawn_hooks() {
+            return self;
+        }
+        let will_be_spawned = self.e.will_be_spawned;
+        self.at_this_moment_apply_commands(|commands| {
             use bevy_scene::EntityCommandsSceneExt;
 
-            commands.queue_apply_scene(f());
+            if will_be_spawned {
+                commands.queue_apply_scene(f());
+            } else {
+                let scene = f();
+                commands.queue(|entity: bevy_ecs::world::EntityWorldMut<'_>| {
+                    reapply_scene_components(entity, scene);
+                });
+            }
         })
     }
 
+    /// Apply [`bevy_scene::Scene`] similarly to [`bevy_scene::EntityCommandsSceneExt::apply_scene`]
+    /// when provided value hash changes (including first function call).
+    ///
+    /// Value hash is tracked per `key`, so multiple scenes can be applied on the same entity.
+    ///
+    /// Scene patch is applied on top of existing entity components. Related entities of scene
+    /// (like `Children`) are spawned again upon each apply, build children in immediate mode instead.
+    #[cfg(feature = "bevy_scene")]
+    pub fn on_hash_change_apply_scene<H, F, S>(mut self, key: &str, value: &H, f: F) -> Self
+    where
+        H: std::hash::Hash,
+        F: FnOnce() -> S,
+        S: bevy_scene::Scene,
+    {
+        let key = imm_id(key);
+        let value = imm_id(value);
+        if self.hash_get(key) != Some(value) {
+            use bevy_scene::EntityCommandsSceneExt;
+
+            self.entity_commands().apply_scene(f());
+            self.hash_set(key, value);
+        }
+        self
+    }
+
+    /// Queue apply [`bevy_scene::Scene`] similarly to [`bevy_scene::EntityCommandsSceneExt::queue_apply_scene`]
+    /// when provided value hash changes (including first function call).
+    ///
+    /// See [`Self::on_hash_change_apply_scene`].
+    #[cfg(feature = "bevy_scene")]
+    pub fn on_hash_change_queue_apply_scene<H, F, S>(mut self, key: &str, value: &H, f: F) -> Self
+    where
+        H: std::hash::Hash,
+        F: FnOnce() -> S,
+        S: bevy_scene::Scene,
+    {
+        let key = imm_id(key);
+        let value = imm_id(value);
+        if self.hash_get(key) != Some(value) {
+            use bevy_scene::EntityCommandsSceneExt;
+
+            self.entity_commands().queue_apply_scene(f());
+            self.hash_set(key, value);
+        }
+        self
+    }
+
     /// Insert [`Bundle`] similarly to [`EntityCommands::insert`].
     /// (inserted only when entity is created).
     pub fn on_spawn_insert<F, B>(self, f: F) -> Self
@@ -613,9 +1245,14 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
         self,
         observer: impl IntoObserverSystem<E, B, M>,
     ) -> Self {
-        self.on_spawn_apply_commands(|commands| {
-            commands.observe(observer);
-        })
+        // Observers are not added again after hotpatch
+        if self.e.will_be_spawned {
+            self.at_this_moment_apply_commands(|commands| {
+                commands.observe(observer);
+            })
+        } else {
+            self
+        }
     }
 
     /// If entity spawned or changed value is `true`, insert [`Bundle`] into entity
@@ -646,21 +1283,71 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
     /// Retrieve component for entity that was requested by capabilities
     ///
     /// Useful in implementing capabilities [`crate::ImmCapabiility`]
+    #[track_caller]
     pub fn cap_get_component<T: Component>(&self) -> Result<Option<&T>, QueryEntityError> {
-        let entity = self.cap_get_entity()?;
-        Ok(entity.get::<T>())
+        let entity = self.cap_get_entity();
+
+        #[cfg(debug_assertions)]
+        self.diagnose_cap_access::<T>(
+            match &entity {
+                Ok(entity) if entity.contains::<T>() && entity.get::<T>().is_none() => {
+                    diagnostics::CapAccess::NotRequested
+                }
+                Ok(_) => diagnostics::CapAccess::Accessible,
+                Err(_) => diagnostics::CapAccess::NotImmediate,
+            },
+            false,
+        );
+
+        Ok(entity?.get::<T>())
     }
 
     /// Retrieve component for entity that was requested by capabilities
     ///
     /// Useful in implementing capabilities [`crate::ImmCapabiility`]
+    #[track_caller]
     pub fn cap_get_component_mut<'a, T: Component<Mutability = Mutable>>(
         &'a mut self,
     ) -> Result<Option<bevy_ecs::world::Mut<'a, T>>, QueryEntityError> {
+        #[cfg(debug_assertions)]
+        {
+            let access = match self.cap_get_entity_mut() {
+                Ok(mut entity) => {
+                    if entity.contains::<T>() && entity.get_mut::<T>().is_none() {
+                        diagnostics::CapAccess::NotRequested
+                    } else {
+                        diagnostics::CapAccess::Accessible
+                    }
+                }
+                Err(_) => diagnostics::CapAccess::NotImmediate,
+            };
+            self.diagnose_cap_access::<T>(access, true);
+        }
+
         let entity = self.cap_get_entity_mut()?;
         Ok(entity.into_mut::<T>())
     }
 
+    /// Report capability data access problems to [`ImmDiagnostics`]
+    #[cfg(debug_assertions)]
+    #[track_caller]
+    fn diagnose_cap_access<T>(&self, access: diagnostics::CapAccess, write: bool) {
+        let Some(diagnostics) = self.imm.ctx.diagnostics.as_deref() else {
+            return;
+        };
+        // Entity is not queryable until spawn commands are applied
+        if self.e.will_be_spawned {
+            return;
+        }
+        diagnostics.cap_access::<T>(
+            access,
+            write,
+            std::panic::Location::caller(),
+            self.e.entity,
+            self.imm.ctx.debug_paths.get(self.e.id).cloned(),
+        );
+    }
+
     /// Retrieve resource from capabilities
     ///
     /// Useful in implementing capabilities [`crate::ImmCapabiility`]
@@ -693,14 +1380,14 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
     ///
     /// It exists only during entity construction
     pub fn cap_entity_tmp_store(&self) -> &ImmTypeMap {
-        &self.tmp_store
+        self.imm.ctx.tmp_stores.get(self.tmp_store)
     }
 
     /// Access data store for current entity.
     ///
     /// It exists only during entity construction
     pub fn cap_entity_tmp_store_mut(&mut self) -> &mut ImmTypeMap {
-        &mut self.tmp_store
+        self.imm.ctx.tmp_stores.get_mut(self.tmp_store)
     }
 
     /// Get [`Entity`] for parent entity of this entity
@@ -708,6 +1395,11 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
         self.imm.current_entity()
     }
 
+    /// Render target of entity tree. See [`Imm::root_target`]
+    pub fn root_target(&self) -> Option<Entity> {
+        self.imm.root_target()
+    }
+
     /// Function to implement change detection using stored hashed value
     ///
     /// Hash values are stored for each entity separately.
@@ -781,6 +1473,69 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
         self.imm.ctx.cached_hash.remove_typ::<UniqueMarker>(entity)
     }
 
+    /// Access typed local state stored for this entity.
+    ///
+    /// State is initialized with `default` when accessed for the first time
+    /// and persists across frames until entity is despawned.
+    ///
+    /// Only one value of each type `T` is stored per entity.
+    /// Use [`Self::local_state_with_key`] if multiple values of the same type are needed.
+    pub fn local_state<T: Send + Sync + 'static>(&mut self, default: impl FnOnce() -> T) -> &mut T {
+        let entity = self.entity();
+        self.imm
+            .ctx
+            .local_state
+            .get_or_insert_with(entity, None, default)
+    }
+
+    /// Access typed local state stored for this entity with given `key`.
+    ///
+    /// See [`Self::local_state`]
+    pub fn local_state_with_key<T: Send + Sync + 'static>(
+        &mut self,
+        key: impl std::hash::Hash,
+        default: impl FnOnce() -> T,
+    ) -> &mut T {
+        let entity = self.entity();
+        self.imm
+            .ctx
+            .local_state
+            .get_or_insert_with(entity, Some(imm_id(key)), default)
+    }
+
+    /// Retrieve typed local state if it has been initialized. See [`Self::local_state`]
+    pub fn local_state_get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        let entity = self.entity();
+        self.imm.ctx.local_state.get::<T>(entity, None)
+    }
+
+    /// Remove typed local state. See [`Self::local_state`]
+    pub fn local_state_remove<T: Send + Sync + 'static>(&mut self) -> Option<T> {
+        let entity = self.entity();
+        self.imm.ctx.local_state.remove::<T>(entity, None)
+    }
+
+    /// Set what happens with this entity when it is not built during frame. See [`ImmRetention`]
+    ///
+    /// Useful for tab panels and wizard pages to keep expensive subtrees alive
+    /// while they are not displayed.
+    ///
+    /// Policy must be provided each time entity is built, otherwise it is removed.
+    /// Retained entity is still despawned together with its parent.
+    pub fn retention(self, policy: ImmRetention) -> Self {
+        let entity = self.e.entity;
+        self.imm.ctx.state.retention_set.insert(entity);
+        self.on_hash_change_typ_insert::<ImmRetention, _, _, _>(&policy, || policy)
+    }
+
+    /// Keep entity alive in leaving phase after it is not built anymore. See [`ImmDeferredDespawn`]
+    ///
+    /// Useful for exit transitions.
+    pub fn defer_despawn(self, timeout_frames: u32) -> Self {
+        let value = ImmDeferredDespawn { timeout_frames };
+        self.on_hash_change_typ_insert::<ImmDeferredDespawn, _, _, _>(&value, || value)
+    }
+
     /// Insert bundle on entity when provided value hash changes (including first function call)
     pub fn on_hash_change_insert<H, F, B>(mut self, key: &str, value: &H, f: F) -> Self
     where
@@ -798,6 +1553,39 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
         self
     }
 
+    /// Build children with provided closure only when value hash changes
+    /// (including first function call)
+    ///
+    /// When hash matches previous build, closure is skipped, but entities that it built
+    /// (including entity trees built with [`Imm::unrooted`] and [`Imm::portal`]) are kept alive
+    /// and keep their order.
+    /// Closure output must depend only on `value`.
+    pub fn memo<H>(mut self, value: &H, f: impl FnOnce(&mut Imm<'w, 's, Caps>)) -> Self
+    where
+        H: std::hash::Hash,
+    {
+        struct MemoMarker;
+
+        let value = imm_id(value);
+        if self.e.will_be_spawned || self.hash_get_typ::<MemoMarker>() != Some(value) {
+            self.hash_set_typ::<MemoMarker>(value);
+            return self.add(f);
+        }
+
+        self.imm.keep_built_alive(Some(self.e.entity), self.e.id);
+        self
+    }
+
+    /// Keep entities built under this entity in previous frames alive without building them
+    ///
+    /// Useful to hide subtree while keeping its entities and their state.
+    /// Entities keep their order. Entity trees built with [`Imm::unrooted`] and [`Imm::portal`]
+    /// are kept too.
+    pub fn keep_children_alive(self) -> Self {
+        self.imm.keep_built_alive(Some(self.e.entity), self.e.id);
+        self
+    }
+
     /// Insert bundle on entity when provided value hash changes (including first function call)
     pub fn on_hash_change_typ_insert<Key, H, F, B>(mut self, value: &H, f: F) -> Self
     where
@@ -824,9 +1612,24 @@ impl<'r, 'w, 's, Caps: CapSet> ImmEntity<'r, 'w, 's, Caps> {
 pub struct ImmMarker<Caps> {
     id: ImmId,
     iteration: u32,
+    #[cfg(debug_assertions)]
+    location: &'static std::panic::Location<'static>,
     _ph: PhantomData<Caps>,
 }
 
+impl<Caps> ImmMarker<Caps> {
+    /// Immediate mode unique id for this entity
+    pub fn imm_id(&self) -> ImmId {
+        self.id
+    }
+
+    /// Source code location where entity was built last time
+    #[cfg(debug_assertions)]
+    pub fn location(&self) -> &'static std::panic::Location<'static> {
+        self.location
+    }
+}
+
 /// Type to use in QueryFilter to avoid query collisions
 pub type WithoutImm<Caps = ()> = Without<ImmMarker<Caps>>;
 
@@ -877,8 +1680,16 @@ impl<'r, 'w, 's, Caps: CapSet> ImmScopeGuard<'r, 'w, 's, Caps> {
         imm: &'r mut Imm<'w, 's, Caps>,
         additional_auto_id: impl std::hash::Hash,
     ) -> Self {
+        #[cfg(debug_assertions)]
+        imm.ctx.debug_paths.set_source(&additional_auto_id);
+
         let auto_id_pref = imm.current.id_pref.with(additional_auto_id);
 
+        #[cfg(debug_assertions)]
+        imm.ctx
+            .debug_paths
+            .prefix(imm.current.id_pref, auto_id_pref);
+
         Self::new_scope(
             imm,
             Current {
@@ -886,6 +1697,10 @@ impl<'r, 'w, 's, Caps: CapSet> ImmScopeGuard<'r, 'w, 's, Caps> {
                 entity: imm.current.entity,
                 auto_id_idx: 0,
                 id_pref: auto_id_pref,
+                root_target: imm.current.root_target,
+                auto_id_mode: imm.current.auto_id_mode,
+                detached: imm.current.detached,
+                depth: imm.current.depth,
             },
         )
     }
@@ -899,6 +1714,40 @@ impl<'r, 'w, 's, Caps: CapSet> ImmScopeGuard<'r, 'w, 's, Caps> {
     }
 }
 
+/// Apply components of [`bevy_scene::Scene`] on existing entity without spawning related entities
+///
+/// Scene is applied on temporary entity. Its components, except hierarchy, are moved to `entity`
+/// and temporary entity is despawned together with related entities spawned by scene.
+#[cfg(feature = "bevy_scene")]
+fn reapply_scene_components<S: bevy_scene::Scene>(
+    mut entity: bevy_ecs::world::EntityWorldMut<'_>,
+    scene: S,
+) {
+    use bevy_ecs::entity::EntityCloner;
+    use bevy_scene::EntityWorldMutSceneExt;
+
+    let target = entity.id();
+    entity.world_scope(|world| {
+        let mut tmp = world.spawn_empty();
+        let result = tmp.apply_scene(scene);
+        let tmp = tmp.id();
+
+        match result {
+            Ok(()) => {
+                EntityCloner::build_opt_out(world)
+                    .deny::<(ChildOf, Children)>()
+                    .move_components(true)
+                    .clone_entity(tmp, target);
+            }
+            Err(err) => {
+                log::error!("Failed to apply scene again on {target}: {err}");
+            }
+        }
+
+        world.despawn(tmp);
+    });
+}
+
 trait FnOnceImm<'w, 's, Caps: CapSet, R> {
     fn call(self, imm: &mut Imm<'w, 's, Caps>) -> R;
 }
@@ -0,0 +1,136 @@
+use std::marker::PhantomData;
+
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    entity_disabling::Disabled,
+    query::{Allow, With},
+    resource::Resource,
+    system::Query,
+};
+use bevy_platform::collections::HashMap;
+
+use crate::ImmId;
+
+pub fn init<Caps: Send + Sync + 'static>(app: &mut bevy_app::App) {
+    app.insert_resource(ImmEntityPool::<Caps>::default());
+}
+
+/// Pool of immediate mode entities that were not built, but are kept for reuse
+///
+/// Only entities built with pooled variants like [`crate::Imm::ch_id_pooled`] are pooled.
+/// Entities are grouped by kind. When new entity of the same kind needs to be spawned,
+/// pooled entity is reused instead. Reused entity is treated as newly spawned entity, so
+/// `on_spawn_*` functions are executed again.
+///
+/// Pooled entities and their descendants are disabled with [`Disabled`],
+/// so they do not show up in queries (and are not rendered) until they are reused.
+///
+/// Capabilities can reset state of pooled entities using
+/// [`crate::ImmCapAccessRequests::add_on_pool_reset_listener`].
+#[derive(Resource)]
+pub struct ImmEntityPool<Caps> {
+    free: HashMap<ImmId, Vec<Entity>>,
+    capacity_per_kind: usize,
+
+    current: ImmPoolStats,
+    last_frame: ImmPoolStats,
+
+    _ph: PhantomData<Caps>,
+}
+
+impl<Caps> Default for ImmEntityPool<Caps> {
+    fn default() -> Self {
+        Self {
+            free: Default::default(),
+            capacity_per_kind: 256,
+            current: Default::default(),
+            last_frame: Default::default(),
+            _ph: PhantomData,
+        }
+    }
+}
+
+/// Entity reuse counters for single frame
+///
+/// Spawned and despawned entities are counted by [`crate::ImmFrameStats`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ImmPoolStats {
+    /// Entities reused from pool instead of being spawned
+    pub reused: u32,
+    /// Entities moved into pool instead of being despawned
+    pub pooled: u32,
+}
+
+/// Marks entity that is stored in [`ImmEntityPool`]
+///
+/// Pooled entities are [`Disabled`], query them with [`Allow<Disabled>`] filter.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ImmPooled;
+
+/// Query of pooled entities
+pub(super) type ImmPooledQuery<'w, 's> = Query<'w, 's, (), (With<ImmPooled>, Allow<Disabled>)>;
+
+/// Pool kind of entity
+#[derive(Component, Clone, Copy)]
+pub(super) struct ImmPoolKind(pub ImmId);
+
+impl<Caps> ImmEntityPool<Caps> {
+    /// Maximum amount of pooled entities per kind
+    pub fn capacity_per_kind(&self) -> usize {
+        self.capacity_per_kind
+    }
+
+    /// Set maximum amount of pooled entities per kind. Entities over capacity are despawned.
+    pub fn set_capacity_per_kind(&mut self, capacity: usize) {
+        self.capacity_per_kind = capacity;
+    }
+
+    /// Amount of pooled entities
+    pub fn len(&self) -> usize {
+        self.free.values().map(|entities| entities.len()).sum()
+    }
+
+    /// Pool is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reuse counters for last completed frame
+    pub fn last_frame_stats(&self) -> ImmPoolStats {
+        self.last_frame
+    }
+
+    pub(super) fn take(&mut self, kind: ImmId, pooled: &ImmPooledQuery) -> Option<Entity> {
+        let entities = self.free.get_mut(&kind)?;
+        while let Some(entity) = entities.pop() {
+            // Entity could have been despawned while being pooled
+            if pooled.contains(entity) {
+                self.current.reused += 1;
+                return Some(entity);
+            }
+        }
+        None
+    }
+
+    pub(super) fn try_put(&mut self, kind: ImmId, entity: Entity) -> bool {
+        let entities = self.free.entry(kind).or_default();
+        if entities.len() >= self.capacity_per_kind {
+            return false;
+        }
+        entities.push(entity);
+        self.current.pooled += 1;
+        true
+    }
+
+    /// Remove entities that were despawned while pooled
+    pub(super) fn remove_despawned(&mut self, pooled: &ImmPooledQuery) {
+        for entities in self.free.values_mut() {
+            entities.retain(|entity| pooled.contains(*entity));
+        }
+    }
+
+    pub(super) fn finish_frame(&mut self) {
+        self.last_frame = std::mem::take(&mut self.current);
+    }
+}
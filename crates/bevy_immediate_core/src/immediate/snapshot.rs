@@ -0,0 +1,513 @@
+use std::{any::TypeId, collections::BTreeMap};
+
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    hierarchy::{ChildOf, Children},
+    name::Name,
+    reflect::{AppTypeRegistry, ReflectComponent},
+    world::{EntityRef, World},
+};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::serde::TypedReflectSerializer;
+use serde::{Deserialize, Serialize};
+
+use crate::{CapSet, ImmDebugLabel, ImmId, ImmMarker};
+
+/// Selects reflected components that are recorded by [`ImmSnapshot::capture`]
+///
+/// Components must be registered in [`AppTypeRegistry`] with `#[reflect(Component)]`.
+///
+/// ```ignore
+/// let config = ImmSnapshotConfig::new()
+///     .with_component::<Node>()
+///     .with_component::<Text>()
+///     .with_component::<Checked>();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ImmSnapshotConfig {
+    components: Vec<(TypeId, &'static str)>,
+}
+
+impl ImmSnapshotConfig {
+    /// Construct config that records only tree structure
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record component `T` for every entity that contains it
+    pub fn with_component<T: Component>(mut self) -> Self {
+        let type_id = TypeId::of::<T>();
+        if !self.components.iter().any(|(id, _)| *id == type_id) {
+            self.components.push((type_id, std::any::type_name::<T>()));
+        }
+        self
+    }
+}
+
+/// Error returned while capturing or (de)serializing [`ImmSnapshot`]
+#[derive(Debug)]
+pub enum ImmSnapshotError {
+    /// [`AppTypeRegistry`] resource is missing from world
+    MissingTypeRegistry,
+    /// Component is not registered in [`AppTypeRegistry`] with `#[reflect(Component)]`
+    ComponentNotRegistered(&'static str),
+    /// Failed to serialize snapshot or component value
+    Serialize(ron::Error),
+    /// Failed to deserialize snapshot
+    Deserialize(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for ImmSnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImmSnapshotError::MissingTypeRegistry => {
+                write!(f, "AppTypeRegistry resource is missing")
+            }
+            ImmSnapshotError::ComponentNotRegistered(name) => write!(
+                f,
+                "component {name} is not registered with #[reflect(Component)]"
+            ),
+            ImmSnapshotError::Serialize(err) => write!(f, "failed to serialize snapshot: {err}"),
+            ImmSnapshotError::Deserialize(err) => {
+                write!(f, "failed to deserialize snapshot: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImmSnapshotError {}
+
+impl From<ron::Error> for ImmSnapshotError {
+    fn from(value: ron::Error) -> Self {
+        ImmSnapshotError::Serialize(value)
+    }
+}
+
+/// Recorded state of single immediate mode entity
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImmSnapshotNode {
+    /// Immediate mode id of entity
+    ///
+    /// Not serialized, [`ImmId`] is a hash that may differ between platforms and crate versions.
+    /// `None` for nodes restored with [`ImmSnapshot::from_ron`].
+    #[serde(skip)]
+    pub id: Option<ImmId>,
+    /// Path segments of entity and its ancestors separated by `/`
+    ///
+    /// Segment is label of entity. Entities without label are identified by `#` and
+    /// index among immediate mode siblings. Labels repeated among siblings get index suffix (`item#2`).
+    pub path: String,
+    /// Label taken from [`Name`] component or [`ImmDebugLabel`] when entity has no [`Name`]
+    ///
+    /// [`ImmDebugLabel`] is recorded only in debug builds. Use [`Name`] when snapshots
+    /// recorded in debug and release builds must be equal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Path segments of immediate mode children in [`Children`] order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<String>,
+    /// Recorded components in RON format, keyed by short type path
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, String>,
+}
+
+/// Serializable snapshot of entity tree managed by immediate mode for capability set
+///
+/// Nodes are stored in depth first order and identified by label paths. Roots are ordered
+/// by label, children follow [`Children`] order. Output contains neither [`Entity`] nor [`ImmId`]
+/// values, so snapshots can be stored as golden files. Label roots when entity tree has
+/// multiple roots, unlabeled roots are ordered by [`ImmId`].
+///
+/// ```ignore
+/// let snapshot = ImmSnapshot::capture::<CapsUi>(world, &config)?;
+/// let expected = ImmSnapshot::from_ron(include_str!("menu.ron"))?;
+///
+/// let diff = expected.diff(&snapshot);
+/// assert!(diff.is_empty(), "{diff}");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImmSnapshot {
+    /// Recorded entities
+    pub nodes: Vec<ImmSnapshotNode>,
+}
+
+impl ImmSnapshot {
+    /// Record entity tree managed by immediate mode for capability set `Caps`
+    ///
+    /// Entities whose parent is not managed by `Caps` are treated as roots.
+    pub fn capture<Caps: CapSet>(
+        world: &World,
+        config: &ImmSnapshotConfig,
+    ) -> Result<Self, ImmSnapshotError> {
+        let registry = world
+            .get_resource::<AppTypeRegistry>()
+            .ok_or(ImmSnapshotError::MissingTypeRegistry)?
+            .read();
+
+        let components = config
+            .components
+            .iter()
+            .map(|(type_id, type_name)| {
+                let registration = registry
+                    .get(*type_id)
+                    .ok_or(ImmSnapshotError::ComponentNotRegistered(type_name))?;
+                let reflect = registration
+                    .data::<ReflectComponent>()
+                    .ok_or(ImmSnapshotError::ComponentNotRegistered(type_name))?;
+                Ok((
+                    registration.type_info().type_path_table().short_path(),
+                    reflect,
+                ))
+            })
+            .collect::<Result<Vec<_>, ImmSnapshotError>>()?;
+
+        let Some(mut query) = world.try_query::<(Entity, &ImmMarker<Caps>, Option<&ChildOf>)>()
+        else {
+            return Ok(Self::default());
+        };
+
+        let mut roots: Vec<(Entity, ImmId, Option<String>)> = query
+            .iter(world)
+            .filter(|(_, _, child_of)| {
+                child_of.is_none_or(|child_of| {
+                    world.get::<ImmMarker<Caps>>(child_of.parent()).is_none()
+                })
+            })
+            .map(|(entity, marker, _)| {
+                (entity, marker.imm_id(), entity_label(world.entity(entity)))
+            })
+            .collect();
+        roots.sort_by(|a, b| a.2.cmp(&b.2).then(a.1.raw().cmp(&b.1.raw())));
+
+        let mut nodes = Vec::new();
+        let root_segments = path_segments(&roots);
+        let mut stack: Vec<(Entity, ImmId, Option<String>, String)> = roots
+            .into_iter()
+            .zip(root_segments)
+            .rev()
+            .map(|((entity, id, label), path)| (entity, id, label, path))
+            .collect();
+
+        while let Some((entity, id, label, path)) = stack.pop() {
+            let entity_ref = world.entity(entity);
+
+            let children: Vec<(Entity, ImmId, Option<String>)> = entity_ref
+                .get::<Children>()
+                .map(|children| {
+                    children
+                        .iter()
+                        .filter_map(|child| {
+                            world.get::<ImmMarker<Caps>>(*child).map(|marker| {
+                                (*child, marker.imm_id(), entity_label(world.entity(*child)))
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let child_segments = path_segments(&children);
+
+            let mut node_components = BTreeMap::new();
+            for (name, reflect) in components.iter() {
+                let Some(value) = reflect.reflect(entity_ref) else {
+                    continue;
+                };
+                let serializer = TypedReflectSerializer::new(value.as_partial_reflect(), &registry);
+                node_components.insert(name.to_string(), ron::to_string(&serializer)?);
+            }
+
+            stack.extend(children.into_iter().zip(child_segments.iter()).rev().map(
+                |((child, id, label), segment)| (child, id, label, format!("{path}/{segment}")),
+            ));
+
+            nodes.push(ImmSnapshotNode {
+                id: Some(id),
+                path,
+                label,
+                children: child_segments,
+                components: node_components,
+            });
+        }
+
+        Ok(Self { nodes })
+    }
+
+    /// Serialize snapshot to human readable RON
+    pub fn to_ron(&self) -> Result<String, ImmSnapshotError> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::new().struct_names(true),
+        )?)
+    }
+
+    /// Deserialize snapshot from RON produced by [`Self::to_ron`]
+    pub fn from_ron(value: &str) -> Result<Self, ImmSnapshotError> {
+        ron::from_str(value).map_err(ImmSnapshotError::Deserialize)
+    }
+
+    /// Retrieve node by [`ImmId`]
+    ///
+    /// Nodes restored with [`Self::from_ron`] have no ids, use [`Self::node_at`].
+    pub fn node(&self, id: ImmId) -> Option<&ImmSnapshotNode> {
+        self.nodes.iter().find(|node| node.id == Some(id))
+    }
+
+    /// Retrieve node by path. See [`ImmSnapshotNode::path`]
+    pub fn node_at(&self, path: &str) -> Option<&ImmSnapshotNode> {
+        self.nodes.iter().find(|node| node.path == path)
+    }
+
+    /// Compare with newer snapshot
+    ///
+    /// Nodes are matched by path.
+    pub fn diff(&self, after: &ImmSnapshot) -> ImmSnapshotDiff {
+        let before_nodes: HashMap<&str, &ImmSnapshotNode> = self
+            .nodes
+            .iter()
+            .map(|node| (node.path.as_str(), node))
+            .collect();
+        let after_nodes: HashMap<&str, &ImmSnapshotNode> = after
+            .nodes
+            .iter()
+            .map(|node| (node.path.as_str(), node))
+            .collect();
+
+        let mut diff = ImmSnapshotDiff::default();
+
+        for node in self.nodes.iter() {
+            match after_nodes.get(node.path.as_str()) {
+                None => diff.removed.push(node.clone()),
+                Some(after) if !after.same_state(node) => diff.changed.push(ImmSnapshotChange {
+                    before: node.clone(),
+                    after: (*after).clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+
+        for node in after.nodes.iter() {
+            if !before_nodes.contains_key(node.path.as_str()) {
+                diff.added.push(node.clone());
+            }
+        }
+
+        diff
+    }
+}
+
+impl ImmSnapshotNode {
+    /// Nodes have equal recorded state, [`Self::id`] is ignored
+    fn same_state(&self, other: &ImmSnapshotNode) -> bool {
+        self.path == other.path
+            && self.label == other.label
+            && self.children == other.children
+            && self.components == other.components
+    }
+}
+
+/// Label of entity, see [`ImmSnapshotNode::label`]
+fn entity_label(entity: EntityRef<'_>) -> Option<String> {
+    if let Some(name) = entity.get::<Name>() {
+        return Some(name.as_str().to_owned());
+    }
+    entity
+        .get::<ImmDebugLabel>()
+        .map(|label| label.as_str().to_owned())
+}
+
+/// Path segments of siblings, see [`ImmSnapshotNode::path`]
+fn path_segments(siblings: &[(Entity, ImmId, Option<String>)]) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::default();
+    for label in siblings.iter().filter_map(|(_, _, label)| label.as_deref()) {
+        *counts.entry(label).or_default() += 1;
+    }
+
+    siblings
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, _, label))| match label.as_deref() {
+            Some(label) if counts[label] == 1 => label.to_owned(),
+            Some(label) => format!("{label}#{idx}"),
+            None => format!("#{idx}"),
+        })
+        .collect()
+}
+
+/// Node that is present in both snapshots with different state
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImmSnapshotChange {
+    /// State in older snapshot
+    pub before: ImmSnapshotNode,
+    /// State in newer snapshot
+    pub after: ImmSnapshotNode,
+}
+
+impl ImmSnapshotChange {
+    /// Path of changed node
+    pub fn path(&self) -> &str {
+        &self.after.path
+    }
+
+    /// Short type paths of components that were added, removed or changed
+    pub fn changed_components(&self) -> Vec<&str> {
+        let before = &self.before.components;
+        let after = &self.after.components;
+
+        let mut changed: Vec<&str> = before
+            .iter()
+            .filter(|(name, value)| after.get(*name) != Some(*value))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        changed.extend(
+            after
+                .keys()
+                .filter(|name| !before.contains_key(*name))
+                .map(String::as_str),
+        );
+        changed.sort_unstable();
+        changed
+    }
+}
+
+/// Difference between two [`ImmSnapshot`] values. See [`ImmSnapshot::diff`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImmSnapshotDiff {
+    /// Nodes present only in newer snapshot
+    pub added: Vec<ImmSnapshotNode>,
+    /// Nodes present only in older snapshot
+    pub removed: Vec<ImmSnapshotNode>,
+    /// Nodes with changed label, children order or components
+    pub changed: Vec<ImmSnapshotChange>,
+}
+
+impl ImmSnapshotDiff {
+    /// Snapshots are equal
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl std::fmt::Display for ImmSnapshotDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for node in self.added.iter() {
+            writeln!(f, "+ {}", node.path)?;
+        }
+        for node in self.removed.iter() {
+            writeln!(f, "- {}", node.path)?;
+        }
+        for change in self.changed.iter() {
+            let (before, after) = (&change.before, &change.after);
+            writeln!(f, "~ {}", after.path)?;
+
+            if before.label != after.label {
+                writeln!(f, "    label: {:?} -> {:?}", before.label, after.label)?;
+            }
+            if before.children != after.children {
+                writeln!(
+                    f,
+                    "    children: {:?} -> {:?}",
+                    before.children, after.children
+                )?;
+            }
+            for name in change.changed_components() {
+                writeln!(
+                    f,
+                    "    {name}: {} -> {}",
+                    before.components.get(name).map_or("None", String::as_str),
+                    after.components.get(name).map_or("None", String::as_str)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{
+        component::Component,
+        name::Name,
+        reflect::{AppTypeRegistry, ReflectComponent},
+    };
+    use bevy_reflect::Reflect;
+
+    use super::{ImmSnapshot, ImmSnapshotConfig};
+    use crate::testing::ImmTestApp;
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct Value(u32);
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_nodes() {
+        let mut test = ImmTestApp::<()>::new();
+        test.world_mut().init_resource::<AppTypeRegistry>();
+        test.world()
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Value>();
+        let config = ImmSnapshotConfig::new().with_component::<Value>();
+
+        let build = |test: &mut ImmTestApp<()>, value: u32, last: &'static str| {
+            test.run_frame(|ui| {
+                let mut a = ui.ch_id("a");
+                a.entity_commands().insert((Name::new("a"), Value(value)));
+                let a = a.imm_id();
+                let mut last_entity = ui.ch_id(last);
+                last_entity.entity_commands().insert(Name::new(last));
+                (a, last_entity.imm_id())
+            })
+        };
+
+        let (a, b) = build(&mut test, 1, "b");
+        let before = test.snapshot(&config).unwrap();
+        assert!(before.diff(&test.snapshot(&config).unwrap()).is_empty());
+        assert_eq!(before.node(b).unwrap().path, "b");
+
+        let (_, c) = build(&mut test, 2, "c");
+        let after = test.snapshot(&config).unwrap();
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].id, Some(c));
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].id, Some(b));
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].path(), "a");
+        assert_eq!(diff.changed[0].changed_components(), ["Value"]);
+        assert_eq!(diff.changed[0].after.label.as_deref(), Some("a"));
+
+        let ron = after.to_ron().unwrap();
+        assert!(!ron.contains(&a.raw().to_string()));
+        let restored = ImmSnapshot::from_ron(&ron).unwrap();
+        assert!(after.diff(&restored).is_empty());
+        assert_eq!(
+            restored.node_at("a").unwrap().components,
+            after.node(a).unwrap().components
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn label_falls_back_to_debug_label() {
+        let mut test = ImmTestApp::<()>::new();
+
+        let id = test.run_frame(|ui| {
+            ui.ch_id("panel")
+                .debug_label("panel")
+                .add(|ui| {
+                    ui.ch_id(1).debug_label("row");
+                    ui.ch_id(2).debug_label("row");
+                    ui.ch_id(3);
+                })
+                .imm_id()
+        });
+
+        let snapshot = test.snapshot(&ImmSnapshotConfig::new()).unwrap();
+        let node = snapshot.node(id).unwrap();
+        assert_eq!(node.label.as_deref(), Some("panel"));
+        assert_eq!(node.path, "panel");
+        assert_eq!(node.children, ["row#0", "row#1", "#2"]);
+        assert!(snapshot.node_at("panel/#2").is_some());
+    }
+}
@@ -1,5 +1,7 @@
 use std::marker::PhantomData;
 
+use bevy_ecs::schedule::InternedScheduleLabel;
+
 /// System set for systems that power `bevy_immediate` immediate mode functionality
 #[derive(bevy_ecs::schedule::SystemSet)]
 pub struct ImmediateSystemSet<Caps>(PhantomData<Caps>);
@@ -35,3 +37,29 @@ impl<Caps> PartialEq for ImmediateSystemSet<Caps> {
         self.0 == other.0
     }
 }
+
+/// Schedules used by immediate mode for capability set `Caps`
+///
+/// Inserted by [`crate::BevyImmediatePlugin`]. Capabilities should add their upkeep systems
+/// to [`Self::upkeep`] schedule in [`ImmediateSystemSet`].
+#[derive(bevy_ecs::resource::Resource)]
+pub struct ImmSchedules<Caps> {
+    pub(super) build: InternedScheduleLabel,
+    pub(super) upkeep: InternedScheduleLabel,
+    pub(super) _ph: PhantomData<Caps>,
+}
+
+impl<Caps> ImmSchedules<Caps> {
+    /// Schedule in which immediate mode entity trees are built
+    pub fn build(&self) -> InternedScheduleLabel {
+        self.build
+    }
+
+    /// Schedule in which [`ImmediateSystemSet`] systems remove entities that were not built,
+    /// clean caches and apply ordering
+    ///
+    /// Must run after [`Self::build`] schedule in the same frame.
+    pub fn upkeep(&self) -> InternedScheduleLabel {
+        self.upkeep
+    }
+}
@@ -0,0 +1,246 @@
+use bevy_app::App;
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    hierarchy::{ChildOf, Children},
+    query::{With, Without},
+    system::SystemState,
+    world::World,
+};
+
+use crate::{
+    BevyImmediatePlugin, CapSet, Imm, ImmCtx, ImmFrameSkip, ImmId, ImmMarker,
+    immediate::entity_mapping::ImmediateModeEntityMapping,
+};
+
+/// Headless harness to drive immediate mode entity trees without a full Bevy app.
+///
+/// Available with `testing` feature. Enable it for tests through dev-dependencies.
+///
+/// Builds minimal [`App`] with [`BevyImmediatePlugin<Caps>`], runs provided build closure
+/// and executes single app update per frame so that upkeep, cached hashes and
+/// capability systems (like `layout_order`) are processed exactly as in a real app.
+///
+/// ```
+/// # use bevy_immediate_core::testing::ImmTestApp;
+/// let mut test = ImmTestApp::<()>::new();
+///
+/// let (a, b) = test.run_frame(|ui| (ui.ch_id("a").imm_id(), ui.ch_id("b").imm_id()));
+/// let entity_a = test.entity(a).unwrap();
+///
+/// test.run_frame(|ui| {
+///     ui.ch_id("a");
+/// });
+/// assert_eq!(test.entity(a), Some(entity_a)); // Reused
+/// assert_eq!(test.entity(b), None); // Despawned by upkeep
+/// ```
+pub struct ImmTestApp<Caps: CapSet> {
+    app: App,
+    state: SystemState<ImmCtx<'static, 'static, Caps>>,
+    frame: usize,
+}
+
+impl<Caps: CapSet> Default for ImmTestApp<Caps> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Caps: CapSet> ImmTestApp<Caps> {
+    /// Root id used for entity tree built by [`Self::run_frame`]
+    pub const ROOT_ID: &'static str = "bevy_immediate_test_root";
+
+    /// Construct harness with empty [`App`]
+    pub fn new() -> Self {
+        Self::from_app(App::new())
+    }
+
+    /// Construct harness from preconfigured [`App`]
+    ///
+    /// Useful when capabilities require additional plugins or resources (time, input, etc.).
+    /// [`BevyImmediatePlugin<Caps>`] is added if it is missing.
+    pub fn from_app(mut app: App) -> Self {
+        if !app.is_plugin_added::<BevyImmediatePlugin<Caps>>() {
+            app.add_plugins(BevyImmediatePlugin::<Caps>::new());
+        }
+        app.finish();
+        app.cleanup();
+
+        let state = SystemState::new(app.world_mut());
+
+        Self {
+            app,
+            state,
+            frame: 0,
+        }
+    }
+
+    /// Run single frame with provided build closure and return its result
+    ///
+    /// Closure is executed before [`App::update`], afterwards queued commands are applied and
+    /// all schedules (including upkeep) are executed.
+    pub fn run_frame<R>(&mut self, f: impl FnOnce(&mut Imm<'_, '_, Caps>) -> R) -> R {
+        let value = {
+            let ctx = self
+                .state
+                .get_mut(self.app.world_mut())
+                .expect("bevy_immediate test harness system params are not valid");
+
+            let mut imm = ctx.build_immediate_root(Self::ROOT_ID);
+            f(&mut imm)
+        };
+
+        self.state.apply(self.app.world_mut());
+        self.app.update();
+        self.frame += 1;
+
+        value
+    }
+
+    /// Run single frame without rebuilding entity tree. See [`ImmFrameSkip`]
+    pub fn run_skipped_frame(&mut self) {
+        self.world_mut()
+            .resource_mut::<ImmFrameSkip<Caps>>()
+            .skip_frame();
+        self.app.update();
+        self.frame += 1;
+    }
+
+    /// Run `frames` frames with provided build closure
+    ///
+    /// Closure receives frame index starting from 0 for this call.
+    pub fn run_frames(&mut self, frames: usize, mut f: impl FnMut(&mut Imm<'_, '_, Caps>, usize)) {
+        for frame in 0..frames {
+            self.run_frame(|ui| f(ui, frame));
+        }
+    }
+
+    /// Count of frames executed by this harness
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Access underlying [`App`]
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    /// Access underlying [`App`]
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// Access underlying [`World`]
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    /// Access underlying [`World`]
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// Retrieve entity managed by immediate mode with given [`ImmId`]
+    pub fn entity(&self, id: ImmId) -> Option<Entity> {
+        self.world()
+            .resource::<ImmediateModeEntityMapping<Caps>>()
+            .id_to_entity
+            .get(&id)
+            .copied()
+    }
+
+    /// Retrieve [`ImmId`] for entity managed by immediate mode
+    pub fn imm_id(&self, entity: Entity) -> Option<ImmId> {
+        self.world()
+            .get::<ImmMarker<Caps>>(entity)
+            .map(|marker| marker.id)
+    }
+
+    /// Check if entity with given [`ImmId`] exists
+    pub fn contains(&self, id: ImmId) -> bool {
+        self.entity(id).is_some()
+    }
+
+    /// Count of entities managed by immediate mode
+    pub fn entity_count(&self) -> usize {
+        let world = self.world();
+        let Some(mut query) = world.try_query_filtered::<(), With<ImmMarker<Caps>>>() else {
+            return 0;
+        };
+        query.iter(world).count()
+    }
+
+    /// Entities managed by immediate mode that do not have parent entity
+    pub fn roots(&self) -> Vec<Entity> {
+        let world = self.world();
+        let Some(mut query) =
+            world.try_query_filtered::<Entity, (With<ImmMarker<Caps>>, Without<ChildOf>)>()
+        else {
+            return Vec::new();
+        };
+        query.iter(world).collect()
+    }
+
+    /// Parent of given entity
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.world()
+            .get::<ChildOf>(entity)
+            .map(|child_of| child_of.parent())
+    }
+
+    /// Children of given entity in [`Children`] order
+    pub fn children(&self, entity: Entity) -> Vec<Entity> {
+        self.world()
+            .get::<Children>(entity)
+            .map(|children| children.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// [`ImmId`] values for children of given entity in [`Children`] order
+    ///
+    /// Children that are not managed by immediate mode are skipped.
+    pub fn children_ids(&self, entity: Entity) -> Vec<ImmId> {
+        self.children(entity)
+            .into_iter()
+            .filter_map(|child| self.imm_id(child))
+            .collect()
+    }
+
+    /// Check if entity exists
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.world().get_entity(entity).is_ok()
+    }
+
+    /// Check if entity contains component
+    pub fn has_component<T: Component>(&self, entity: Entity) -> bool {
+        self.world().get::<T>(entity).is_some()
+    }
+
+    /// Id collisions detected so far. See [`crate::ImmIdCollisions`]
+    ///
+    /// Available only in debug builds, collisions are not tracked in release builds.
+    #[cfg(debug_assertions)]
+    pub fn id_collisions(&self) -> &[crate::ImmIdCollision] {
+        self.world()
+            .resource::<crate::ImmIdCollisions<Caps>>()
+            .collisions()
+    }
+
+    /// Retrieve component from entity
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        self.world().get::<T>(entity)
+    }
+
+    /// Capture [`crate::ImmSnapshot`] of entity tree
+    ///
+    /// [`bevy_ecs::reflect::AppTypeRegistry`] is initialized if it is missing.
+    #[cfg(feature = "snapshot")]
+    pub fn snapshot(
+        &mut self,
+        config: &crate::ImmSnapshotConfig,
+    ) -> Result<crate::ImmSnapshot, crate::ImmSnapshotError> {
+        self.world_mut()
+            .init_resource::<bevy_ecs::reflect::AppTypeRegistry>();
+        crate::ImmSnapshot::capture::<Caps>(self.world(), config)
+    }
+}
@@ -0,0 +1,633 @@
+use bevy_app::{App, Update};
+use bevy_ecs::{
+    component::Component, entity_disabling::Disabled, hierarchy::ChildOf, resource::Resource,
+    schedule::IntoScheduleConfigs, system::ResMut, world::World,
+};
+
+use crate::{
+    BevyImmediatePlugin, CapSet, ImmAutoIdMode, ImmCapAccessRequests, ImmCapability, ImmCtx,
+    ImmEntityPool, ImmFrameSkip, ImmFrameStats, ImmId, ImmIdlePlugin, ImmIdleSystems, ImmLeaving,
+    ImmPooled, ImmRetained, ImmRetention, ImmWorld, ImplCapsEmpty, imm_rebuild_needed,
+    impl_capability_set, testing::ImmTestApp,
+};
+
+type TestApp = ImmTestApp<()>;
+
+#[test]
+fn upkeep_despawns_entities_that_are_not_built() {
+    let mut test = TestApp::new();
+
+    let (a, b, b_child) = test.run_frame(|ui| {
+        let a = ui.ch_id("a").imm_id();
+        let mut b_child = None;
+        let b = ui
+            .ch_id("b")
+            .add(|ui| b_child = Some(ui.ch_id("child").imm_id()))
+            .imm_id();
+        (a, b, b_child.unwrap())
+    });
+    let entity_b = test.entity(b).unwrap();
+    let entity_b_child = test.entity(b_child).unwrap();
+    assert_eq!(test.entity_count(), 3);
+
+    test.run_frame(|ui| {
+        ui.ch_id("a");
+    });
+
+    assert!(test.contains(a));
+    assert!(!test.contains(b));
+    assert!(!test.contains(b_child));
+    assert!(!test.is_alive(entity_b));
+    assert!(!test.is_alive(entity_b_child));
+    assert_eq!(test.entity_count(), 1);
+    assert_eq!(
+        test.world()
+            .resource::<ImmFrameStats<()>>()
+            .last_frame()
+            .despawned,
+        2
+    );
+}
+
+#[test]
+fn entities_are_reused_between_frames() {
+    let build = |ui: &mut crate::Imm<()>| {
+        let mut ids = vec![];
+        ui.ch_id("parent").add(|ui| {
+            ids.push(ui.current_imm_id());
+            for idx in 0..3 {
+                ids.push(ui.ch_id(idx).imm_id());
+            }
+        });
+        ids
+    };
+
+    let mut test = TestApp::new();
+    let ids = test.run_frame(build);
+    let entities: Vec<_> = ids.iter().map(|id| test.entity(*id).unwrap()).collect();
+
+    for _ in 0..3 {
+        assert_eq!(test.run_frame(build), ids);
+        let reused: Vec<_> = ids.iter().map(|id| test.entity(*id).unwrap()).collect();
+        assert_eq!(reused, entities);
+        assert_eq!(
+            test.world()
+                .resource::<ImmFrameStats<()>>()
+                .last_frame()
+                .spawned,
+            0
+        );
+    }
+
+    let parent = entities[0];
+    assert_eq!(test.children(parent), entities[1..]);
+    #[cfg(debug_assertions)]
+    assert!(test.id_collisions().is_empty());
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn id_collision_is_reported_once_per_call_site_pair() {
+    let mut test = TestApp::new();
+
+    for frame in 0..3 {
+        test.run_frame(|ui| {
+            ui.ch_id(("dup", frame));
+            ui.ch_id(("dup", frame));
+        });
+    }
+
+    let collisions = test.id_collisions();
+    assert_eq!(collisions.len(), 1);
+    assert_ne!(collisions[0].first.location, collisions[0].second.location);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn diagnostics_report_id_collisions_after_they_were_taken() {
+    use crate::{
+        ImmDiagnosticKind, ImmDiagnostics, ImmDiagnosticsMode, ImmDiagnosticsPlugin,
+        ImmIdCollisions,
+    };
+
+    let mut app = App::new();
+    app.add_plugins((
+        BevyImmediatePlugin::<()>::new(),
+        ImmDiagnosticsPlugin::<()>::new().with_mode(ImmDiagnosticsMode::Collect),
+    ));
+    let mut test = TestApp::from_app(app);
+
+    test.run_frame(|ui| {
+        ui.ch_id("a");
+        ui.ch_id("a");
+    });
+    assert_eq!(test.id_collisions().len(), 1);
+    test.world_mut()
+        .resource_mut::<ImmIdCollisions<()>>()
+        .take();
+
+    test.run_frame(|ui| {
+        ui.ch_id("b");
+        ui.ch_id("b");
+    });
+
+    let diagnostics = test.world().resource::<ImmDiagnostics<()>>();
+    let collisions = diagnostics
+        .diagnostics()
+        .iter()
+        .filter(|diagnostic| matches!(diagnostic.kind, ImmDiagnosticKind::IdCollision(_)))
+        .count();
+    assert_eq!(collisions, 2);
+}
+
+#[test]
+fn retention_keeps_entity_for_given_frames() {
+    let mut test = TestApp::new();
+
+    let (a, child) = test.run_frame(|ui| {
+        let mut child = None;
+        let a = ui
+            .ch_id("a")
+            .retention(ImmRetention::KeepFrames(2))
+            .add(|ui| child = Some(ui.ch_id("child").imm_id()))
+            .imm_id();
+        (a, child.unwrap())
+    });
+    let entity = test.entity(a).unwrap();
+    let child_entity = test.entity(child).unwrap();
+
+    test.run_frame(|_| {});
+    assert!(test.has_component::<ImmRetained>(entity));
+    assert!(test.is_alive(child_entity));
+
+    // Built again. Retained entity is reused
+    test.run_frame(|ui| {
+        ui.ch_id("a").retention(ImmRetention::KeepFrames(2));
+    });
+    assert_eq!(test.entity(a), Some(entity));
+    assert!(!test.has_component::<ImmRetained>(entity));
+
+    test.run_frame(|_| {});
+    test.run_frame(|_| {});
+    assert!(test.is_alive(entity));
+
+    test.run_frame(|_| {});
+    assert!(!test.is_alive(entity));
+    assert!(!test.contains(a));
+}
+
+#[test]
+fn retention_policy_is_released_when_not_provided() {
+    let mut test = TestApp::new();
+
+    let a = test.run_frame(|ui| {
+        ui.ch_id("a")
+            .retention(ImmRetention::KeepUntilReleased)
+            .imm_id()
+    });
+    let entity = test.entity(a).unwrap();
+    assert!(test.has_component::<ImmRetention>(entity));
+
+    test.run_frame(|ui| {
+        ui.ch_id("a");
+    });
+    assert!(!test.has_component::<ImmRetention>(entity));
+
+    test.run_frame(|_| {});
+    assert!(!test.is_alive(entity));
+}
+
+#[test]
+fn deferred_despawn_keeps_leaving_entity_until_finished() {
+    let mut test = TestApp::new();
+
+    let a = test.run_frame(|ui| ui.ch_id("a").defer_despawn(10).imm_id());
+    let entity = test.entity(a).unwrap();
+
+    test.run_frame(|_| {});
+    assert!(test.is_alive(entity));
+    assert!(test.has_component::<ImmLeaving>(entity));
+    // Id is released for newly built entities
+    assert!(!test.contains(a));
+
+    test.run_frame(|ui| {
+        ui.ch_id("a");
+    });
+    let rebuilt = test.entity(a).unwrap();
+    assert_ne!(rebuilt, entity);
+    assert!(test.is_alive(entity));
+    assert!(test.get::<ImmLeaving>(entity).unwrap().frames() > 0);
+
+    test.world_mut()
+        .get_mut::<ImmLeaving>(entity)
+        .unwrap()
+        .finish();
+    test.run_frame(|ui| {
+        ui.ch_id("a");
+    });
+    assert!(!test.is_alive(entity));
+    assert!(test.is_alive(rebuilt));
+}
+
+#[test]
+fn deferred_despawn_times_out() {
+    let mut test = TestApp::new();
+
+    let a = test.run_frame(|ui| ui.ch_id("a").defer_despawn(3).imm_id());
+    let entity = test.entity(a).unwrap();
+
+    test.run_frame(|_| {});
+    test.run_frames(2, |_, _| {});
+    assert!(test.is_alive(entity));
+
+    test.run_frames(2, |_, _| {});
+    assert!(!test.is_alive(entity));
+}
+
+#[test]
+fn leaving_does_not_start_inside_retained_subtree() {
+    let mut test = TestApp::new();
+
+    let build = |ui: &mut crate::Imm<()>| {
+        let mut item = None;
+        ui.ch_id("page")
+            .retention(ImmRetention::KeepUntilReleased)
+            .add(|ui| item = Some(ui.ch_id("item").defer_despawn(5).imm_id()));
+        item.unwrap()
+    };
+
+    let item = test.run_frame(build);
+    let entity = test.entity(item).unwrap();
+
+    test.run_frames(2, |_, _| {});
+    assert!(!test.has_component::<ImmLeaving>(entity));
+    assert_eq!(test.imm_id(entity), Some(item));
+
+    test.run_frame(build);
+    assert_eq!(test.entity(item), Some(entity));
+}
+
+#[test]
+fn pooled_entities_are_reused() {
+    let mut test = TestApp::new();
+
+    let ids = test.run_frame(|ui| {
+        (0..3)
+            .map(|idx| ui.ch_id_pooled(idx, "row").imm_id())
+            .collect::<Vec<_>>()
+    });
+    let entities: Vec<_> = ids.iter().map(|id| test.entity(*id).unwrap()).collect();
+    let details: Vec<_> = entities
+        .iter()
+        .map(|entity| test.world_mut().spawn(ChildOf(*entity)).id())
+        .collect();
+
+    test.run_frame(|ui| {
+        ui.ch_id_pooled(0, "row");
+    });
+    let pool = test.world().resource::<ImmEntityPool<()>>();
+    assert_eq!(pool.len(), 2);
+    assert_eq!(pool.last_frame_stats().pooled, 2);
+    for &entity in &entities[1..] {
+        assert!(test.is_alive(entity));
+        assert!(test.has_component::<ImmPooled>(entity));
+        assert!(test.has_component::<Disabled>(entity));
+        assert_eq!(test.imm_id(entity), None);
+    }
+    assert!(!test.has_component::<Disabled>(details[0]));
+    assert!(
+        details[1..]
+            .iter()
+            .all(|detail| test.has_component::<Disabled>(*detail))
+    );
+
+    let new_ids = test.run_frame(|ui| {
+        (0..3)
+            .map(|idx| ui.ch_id_pooled(idx + 10, "row").imm_id())
+            .collect::<Vec<_>>()
+    });
+    let pool = test.world().resource::<ImmEntityPool<()>>();
+    let stats = pool.last_frame_stats();
+    assert_eq!(stats.reused, 2);
+    assert_eq!(stats.pooled, 1);
+    assert!(pool.len() == 1);
+    let frame_stats = test.world().resource::<ImmFrameStats<()>>().last_frame();
+    assert_eq!(frame_stats.spawned, 1);
+
+    let mut reused: Vec<_> = new_ids.iter().filter_map(|id| test.entity(*id)).collect();
+    reused.retain(|entity| entities[1..].contains(entity));
+    assert_eq!(reused.len(), 2);
+    for entity in reused {
+        assert!(!test.has_component::<ImmPooled>(entity));
+        assert!(!test.has_component::<Disabled>(entity));
+    }
+    assert!(
+        !details[1..]
+            .iter()
+            .any(|detail| test.has_component::<Disabled>(*detail))
+    );
+}
+
+#[test]
+fn keyed_entities_keep_identity_when_reordered() {
+    let build = |keys: [u32; 3]| {
+        move |ui: &mut crate::Imm<()>| {
+            let mut ids = vec![];
+            ui.for_each_keyed(
+                keys,
+                |key| *key,
+                |ui, key| ids.push((key, ui.ch().imm_id())),
+            );
+            ids
+        }
+    };
+
+    let mut test = TestApp::new();
+    let before = test.run_frame(build([1, 2, 3]));
+    let entities: Vec<_> = before
+        .iter()
+        .map(|(key, id)| (*key, test.entity(*id).unwrap()))
+        .collect();
+
+    let after = test.run_frame(build([3, 1, 2]));
+    for (key, id) in after {
+        let (_, before_id) = before.iter().find(|(k, _)| *k == key).unwrap();
+        assert_eq!(id, *before_id);
+
+        let (_, entity) = entities.iter().find(|(k, _)| *k == key).unwrap();
+        assert_eq!(test.entity(id), Some(*entity));
+    }
+    assert_eq!(
+        test.world()
+            .resource::<ImmFrameStats<()>>()
+            .last_frame()
+            .spawned,
+        0
+    );
+}
+
+#[test]
+fn memo_skips_closure_and_keeps_entities_alive() {
+    let build = |test: &mut TestApp, value: u32, runs: &mut u32| {
+        test.run_frame(|ui| {
+            let mut ids = None;
+            ui.ch_id("memo").memo(&value, |ui| {
+                *runs += 1;
+                let child = ui.ch_id("child").imm_id();
+                let mut popup = None;
+                ui.unrooted("popup", |ui| popup = Some(ui.ch_id("content").imm_id()));
+                ids = Some((child, popup.unwrap()));
+            });
+            ids
+        })
+    };
+
+    let mut test = TestApp::new();
+    let mut runs = 0;
+
+    let (child, popup) = build(&mut test, 1, &mut runs).unwrap();
+    let child_entity = test.entity(child).unwrap();
+    let popup_entity = test.entity(popup).unwrap();
+
+    for _ in 0..3 {
+        assert_eq!(build(&mut test, 1, &mut runs), None);
+        assert_eq!(runs, 1);
+        assert_eq!(test.entity(child), Some(child_entity));
+        assert_eq!(test.entity(popup), Some(popup_entity));
+    }
+
+    assert_eq!(build(&mut test, 2, &mut runs), Some((child, popup)));
+    assert_eq!(runs, 2);
+    assert_eq!(test.entity(child), Some(child_entity));
+}
+
+#[test]
+fn memo_keeps_retention_policy_of_children() {
+    let mut test = TestApp::new();
+
+    let build = |value: u32| {
+        move |ui: &mut crate::Imm<()>| {
+            ui.ch_id("memo")
+                .memo(&value, |ui| {
+                    if value == 1 {
+                        ui.ch_id("child").retention(ImmRetention::KeepUntilReleased);
+                    }
+                })
+                .entity()
+        }
+    };
+
+    let memo = test.run_frame(build(1));
+    let child = test.children(memo)[0];
+
+    test.run_frames(3, |ui, _| {
+        build(1)(ui);
+    });
+    assert!(test.has_component::<ImmRetention>(child));
+    assert!(!test.has_component::<ImmRetained>(child));
+
+    // Child is not built anymore, policy is still in effect
+    test.run_frame(build(2));
+    assert!(test.is_alive(child));
+    assert!(test.has_component::<ImmRetained>(child));
+}
+
+#[test]
+fn skipped_frame_keeps_entity_tree() {
+    let mut test = TestApp::new();
+
+    let a = test.run_frame(|ui| ui.ch_id("a").imm_id());
+    let entity = test.entity(a).unwrap();
+
+    test.run_skipped_frame();
+    test.run_skipped_frame();
+    assert!(test.is_alive(entity));
+    assert_eq!(test.entity(a), Some(entity));
+    assert!(!test.world().resource::<ImmFrameSkip<()>>().is_skipped());
+
+    test.run_frame(|_| {});
+    assert!(!test.is_alive(entity));
+}
+
+#[derive(Resource, Default)]
+struct OtherRoot {
+    skip: bool,
+    builds: u32,
+    child: Option<ImmId>,
+}
+
+fn build_other_root(ctx: ImmCtx<()>, mut other: ResMut<OtherRoot>) {
+    if other.skip {
+        ctx.skip_root("other");
+        return;
+    }
+    other.builds += 1;
+    other.child = Some(ctx.build_immediate_root("other").ch_id("child").imm_id());
+}
+
+#[test]
+fn skipped_root_keeps_entity_tree() {
+    let mut app = App::new();
+    app.init_resource::<OtherRoot>()
+        .add_systems(Update, build_other_root);
+    let mut test = TestApp::from_app(app);
+
+    let a = test.run_frame(|ui| ui.ch_id("a").imm_id());
+    let other = test.world().resource::<OtherRoot>().child.unwrap();
+    let other_entity = test.entity(other).unwrap();
+
+    test.world_mut().resource_mut::<OtherRoot>().skip = true;
+    test.run_frames(3, |_, _| {});
+    // Entity tree of skipped root is kept, other roots are maintained
+    assert_eq!(test.entity(other), Some(other_entity));
+    assert!(!test.contains(a));
+
+    test.world_mut().resource_mut::<OtherRoot>().skip = false;
+    test.run_frame(|_| {});
+    assert_eq!(test.entity(other), Some(other_entity));
+}
+
+#[derive(Resource, Default)]
+struct Watched(u32);
+
+#[test]
+fn idle_plugin_skips_frames_until_activity() {
+    let mut app = App::new();
+    app.add_plugins(BevyImmediatePlugin::<()>::new())
+        .add_plugins(
+            ImmIdlePlugin::<()>::new()
+                .watch_resource::<Watched>()
+                .awake_frames(1),
+        )
+        .init_resource::<Watched>()
+        .init_resource::<OtherRoot>()
+        .add_systems(
+            Update,
+            build_other_root
+                .after(ImmIdleSystems::Decide)
+                .run_if(imm_rebuild_needed::<()>),
+        );
+    let mut test = TestApp::from_app(app);
+
+    let builds = |test: &TestApp| test.world().resource::<OtherRoot>().builds;
+
+    test.app_mut().update();
+    let other = test.world().resource::<OtherRoot>().child.unwrap();
+    let other_entity = test.entity(other).unwrap();
+    assert_eq!(builds(&test), 1);
+
+    for _ in 0..3 {
+        test.app_mut().update();
+    }
+    assert_eq!(builds(&test), 1);
+    assert_eq!(test.entity(other), Some(other_entity));
+
+    test.world_mut().resource_mut::<Watched>().0 += 1;
+    test.app_mut().update();
+    assert_eq!(builds(&test), 2);
+    test.app_mut().update();
+    assert_eq!(builds(&test), 2);
+
+    test.world_mut()
+        .resource_mut::<ImmFrameSkip<()>>()
+        .request_rebuild();
+    test.app_mut().update();
+    assert_eq!(builds(&test), 3);
+    assert_eq!(test.entity(other), Some(other_entity));
+}
+
+#[derive(Component, Debug, PartialEq)]
+struct Value(u32);
+
+pub struct CapabilityTestValue;
+
+impl ImmCapability for CapabilityTestValue {
+    fn build<Caps: CapSet>(app: &mut App, cap_req: &mut ImmCapAccessRequests<Caps>) {
+        cap_req.request_component_write::<Value>(app.world_mut());
+    }
+}
+
+struct CapsTestValue;
+impl_capability_set!(
+    CapsTestValue,
+    ImplCapsTestValue > ImplCapsEmpty,
+    (CapabilityTestValue)
+);
+
+/// Spawn two entities and read their components during the same build
+fn build_and_read_values<Caps: ImplCapsTestValue>(world: &mut World) -> (bool, u32, u32) {
+    ImmWorld::<Caps>::new(world).build_immediate_root("world", |ui| {
+        let first = ui.ch_id("first").on_spawn_insert(|| Value(1)).entity();
+
+        let mut second = ui.ch_id("second").on_spawn_insert(|| Value(2));
+        let spawned = second.will_be_spawned();
+        second.cap_get_component_mut::<Value>().unwrap().unwrap().0 += 10;
+        let second_value = second.cap_get_component::<Value>().unwrap().unwrap().0;
+
+        let first_value = ui
+            .ctx()
+            .cap_entities
+            .get(first)
+            .unwrap()
+            .get::<Value>()
+            .unwrap()
+            .0;
+        (spawned, first_value, second_value)
+    })
+}
+
+#[test]
+fn imm_world_gives_access_to_entities_spawned_during_build() {
+    let mut test = ImmTestApp::<CapsTestValue>::new();
+
+    assert_eq!(
+        build_and_read_values::<CapsTestValue>(test.world_mut()),
+        (true, 1, 12)
+    );
+    assert_eq!(
+        build_and_read_values::<CapsTestValue>(test.world_mut()),
+        (false, 1, 22)
+    );
+}
+
+#[test]
+fn call_site_ids_are_stable_when_siblings_are_built_conditionally() {
+    let build = |mode: ImmAutoIdMode, extra: bool| {
+        move |ui: &mut crate::Imm<()>| {
+            ui.set_auto_id_mode(mode);
+            if extra {
+                ui.ch();
+            }
+            ui.ch().imm_id()
+        }
+    };
+
+    let mut test = TestApp::new();
+    let id = test.run_frame(build(ImmAutoIdMode::CallSite, false));
+    let entity = test.entity(id).unwrap();
+
+    assert_eq!(test.run_frame(build(ImmAutoIdMode::CallSite, true)), id);
+    assert_eq!(test.entity(id), Some(entity));
+    assert_eq!(test.entity_count(), 2);
+    #[cfg(debug_assertions)]
+    assert!(test.id_collisions().is_empty());
+
+    let id = test.run_frame(build(ImmAutoIdMode::Index, false));
+    assert_ne!(test.run_frame(build(ImmAutoIdMode::Index, true)), id);
+}
+
+#[test]
+fn repeated_call_site_gets_unique_ids() {
+    let mut test = TestApp::new();
+
+    let ids = test.run_frame(|ui| {
+        ui.set_auto_id_mode(ImmAutoIdMode::CallSite);
+        (0..3).map(|_| ui.ch().imm_id()).collect::<Vec<_>>()
+    });
+
+    assert_eq!(test.entity_count(), 3);
+    assert_ne!(ids[0], ids[1]);
+    assert_ne!(ids[1], ids[2]);
+    #[cfg(debug_assertions)]
+    assert!(test.id_collisions().is_empty());
+}
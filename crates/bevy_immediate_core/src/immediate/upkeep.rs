@@ -1,19 +1,32 @@
 use std::marker::PhantomData;
 
 use bevy_ecs::{
+    component::Component,
     entity::Entity,
-    query::With,
-    schedule::IntoScheduleConfigs,
-    system::{Commands, Query, ResMut},
+    entity_disabling::Disabled,
+    hierarchy::{ChildOf, Children},
+    query::{Has, With},
+    schedule::{InternedScheduleLabel, IntoScheduleConfigs},
+    system::{Commands, Query, Res, ResMut},
 };
+use bevy_platform::collections::HashSet;
 
-use crate::{ImmMarker, ImmediateSystemSet};
+use crate::{
+    CapSet, ImmCapAccessRequestsResource, ImmDeferredDespawn, ImmEntityPool, ImmFrameStats,
+    ImmLeaving, ImmMarker, ImmPooled, ImmediateSystemSet, imm_rebuild_needed,
+    immediate::{
+        cached_hash::CachedHash,
+        local_state::LocalState,
+        pool::{ImmPoolKind, ImmPooledQuery},
+    },
+};
 
-pub fn init<Caps: Send + Sync + 'static>(app: &mut bevy_app::App) {
+pub fn init<Caps: CapSet>(app: &mut bevy_app::App, schedule: InternedScheduleLabel) {
     app.add_systems(
-        bevy_app::PostUpdate,
+        schedule,
         immediate_mode_tracked_entity_upkeep_system::<Caps>
-            .in_set(ImmediateSystemSet::<Caps>::default()),
+            .in_set(ImmediateSystemSet::<Caps>::default())
+            .run_if(imm_rebuild_needed::<Caps>),
     );
     app.insert_resource(ImmediateModeStateResource::<Caps>::default());
 }
@@ -23,6 +36,17 @@ pub(super) struct ImmediateModeStateResource<Caps: Send + Sync + 'static> {
     // Current iteration for unused entity removal
     pub(super) iteration: u32,
 
+    // Reused buffers for upkeep
+    unused: Vec<Entity>,
+    retained_roots: Vec<Entity>,
+    leaving_roots: Vec<Entity>,
+    retained: HashSet<Entity>,
+    pooled: HashSet<Entity>,
+
+    /// Entities for which [`crate::ImmEntity::retention`] was called during current frame
+    /// or whose policy was kept together with subtree that was not rebuilt
+    pub(super) retention_set: HashSet<Entity>,
+
     _ph: PhantomData<Caps>,
 }
 
@@ -30,24 +54,204 @@ impl<Caps: Send + Sync + 'static> Default for ImmediateModeStateResource<Caps> {
     fn default() -> Self {
         Self {
             iteration: Default::default(),
+            unused: Default::default(),
+            retained_roots: Default::default(),
+            leaving_roots: Default::default(),
+            retained: Default::default(),
+            pooled: Default::default(),
+            retention_set: Default::default(),
             _ph: Default::default(),
         }
     }
 }
 
-fn immediate_mode_tracked_entity_upkeep_system<Caps: Send + Sync + 'static>(
-    query: Query<(Entity, &ImmMarker<Caps>), With<ImmMarker<Caps>>>,
+/// Describes what happens with immediate mode entity when it is not built during frame
+///
+/// By default entities that are not built are despawned.
+///
+/// Retained entities are kept together with their descendants and marked with [`ImmRetained`].
+/// When entity is built again, the same entities are reused and [`ImmRetained`] is removed.
+/// UI capabilities hide retained entities.
+///
+/// Remove component or replace it with [`ImmRetention::Despawn`] to release retained entity.
+/// When policy is set with [`crate::ImmEntity::retention`], it is removed once entity is built
+/// without calling it. Entities that are kept alive without being built
+/// (for example, with [`crate::ImmEntity::memo`]) keep their policy.
+///
+/// Retention applies only to entity itself not being built. Retained entity is still despawned
+/// together with its parent when parent is despawned.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum ImmRetention {
+    /// Despawn entity at the end of frame in which it was not built
+    #[default]
+    Despawn,
+    /// Keep entity for given amount of frames since it was built last time
+    KeepFrames(u32),
+    /// Keep entity until it is released
+    KeepUntilReleased,
+}
+
+/// Marks immediate mode entity which was not built, but is kept due to [`ImmRetention`]
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ImmRetained {
+    since_iteration: u32,
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(super) fn immediate_mode_tracked_entity_upkeep_system<Caps: CapSet>(
+    query: Query<
+        (
+            Entity,
+            &ImmMarker<Caps>,
+            Option<&ImmRetention>,
+            Option<&ImmRetained>,
+            Has<ImmDeferredDespawn>,
+            Option<&ImmPoolKind>,
+        ),
+        With<ImmMarker<Caps>>,
+    >,
+    children: Query<&Children>,
+    pooled: ImmPooledQuery,
     mut state: ResMut<ImmediateModeStateResource<Caps>>,
+    mut pool: ResMut<ImmEntityPool<Caps>>,
+    mut cached_hash: ResMut<CachedHash<Caps>>,
+    mut local_state: ResMut<LocalState<Caps>>,
+    mut frame_stats: ResMut<ImmFrameStats<Caps>>,
+    access_requests: Res<ImmCapAccessRequestsResource<Caps>>,
     mut commands: Commands,
 ) {
-    for (entity, marker) in query {
+    let state = &mut *state;
+    pool.remove_despawned(&pooled);
+
+    for (entity, marker, retention, retained, deferred, _) in query {
         if marker.iteration == state.iteration {
+            // Policy set by `retention` is released when it is not provided anymore
+            if retention.is_some()
+                && !state.retention_set.contains(&entity)
+                && cached_hash.remove_typ::<ImmRetention>(entity).is_some()
+            {
+                commands.entity(entity).remove::<ImmRetention>();
+            }
+            continue;
+        }
+
+        let since_iteration = retained
+            .map(|retained| retained.since_iteration)
+            .unwrap_or(state.iteration);
+
+        let keep = match retention.copied().unwrap_or_default() {
+            ImmRetention::Despawn => false,
+            ImmRetention::KeepFrames(frames) => {
+                state.iteration.wrapping_sub(since_iteration) < frames
+            }
+            ImmRetention::KeepUntilReleased => true,
+        };
+
+        if keep {
+            if retained.is_none() {
+                commands
+                    .entity(entity)
+                    .insert(ImmRetained { since_iteration });
+            }
+            state.retained_roots.push(entity);
+        } else if deferred {
+            state.leaving_roots.push(entity);
+        } else {
+            state.unused.push(entity);
+        }
+    }
+
+    // Descendants of retained entities are not built either, keep them too
+    for root in state.retained_roots.drain(..) {
+        state.retained.insert(root);
+        state
+            .retained
+            .extend(children.iter_descendants::<Children>(root));
+    }
+
+    // Entities inside retained subtree are kept, they do not start leaving
+    let retained = &state.retained;
+    state
+        .leaving_roots
+        .retain(|entity| !retained.contains(entity));
+
+    // Leaving entities and their descendants stop being managed by immediate mode,
+    // so that their ids can be used by newly built entities
+    for root in state.leaving_roots.drain(..) {
+        state.retained.insert(root);
+        commands
+            .entity(root)
+            .remove::<(ImmMarker<Caps>, ImmRetained)>()
+            .insert((
+                ImmLeaving::default(),
+                super::leaving::ImmLeavingOwner::<Caps>::default(),
+            ));
+
+        for entity in children.iter_descendants::<Children>(root) {
+            state.retained.insert(entity);
+            if query.contains(entity) {
+                commands
+                    .entity(entity)
+                    .remove::<(ImmMarker<Caps>, ImmRetained)>();
+            }
+        }
+    }
+
+    // Pooled entities are detached before ancestors are despawned
+    for &entity in state.unused.iter() {
+        if state.retained.contains(&entity) {
+            continue;
+        }
+        let Ok((_, _, _, _, _, Some(kind))) = query.get(entity) else {
+            continue;
+        };
+        if !pool.try_put(kind.0, entity) {
+            continue;
+        }
+
+        let mut entity_commands = commands.entity(entity);
+        entity_commands
+            .remove::<(
+                ImmMarker<Caps>,
+                ChildOf,
+                ImmRetention,
+                ImmRetained,
+                ImmDeferredDespawn,
+            )>()
+            .insert((ImmPooled, Disabled));
+        for on_pool_reset in access_requests.capabilities.on_pool_reset.iter() {
+            (on_pool_reset)(&mut entity_commands);
+        }
+        state.pooled.insert(entity);
+
+        // Immediate mode descendants are pooled, despawned or kept on their own
+        let mut stack: Vec<Entity> = children.relationship_sources::<Children>(entity).collect();
+        while let Some(descendant) = stack.pop() {
+            if query.contains(descendant) {
+                continue;
+            }
+            commands.entity(descendant).insert(Disabled);
+            stack.extend(children.relationship_sources::<Children>(descendant));
+        }
+    }
+
+    for entity in state.unused.drain(..) {
+        if state.retained.contains(&entity) || state.pooled.contains(&entity) {
             continue;
         }
         // Try is used because
         // it is expected that ancestor may have already removed this entity
         commands.entity(entity).try_despawn();
+        frame_stats.current_mut().despawned += 1;
     }
+    state.retained.clear();
+    state.retention_set.clear();
+
+    // Reused entity must not observe state from previous usage
+    cached_hash.remove_entities(&state.pooled);
+    local_state.remove_entities(&state.pooled);
+    state.pooled.clear();
+    pool.finish_frame();
 
     state.iteration = state.iteration.wrapping_add(1);
 }
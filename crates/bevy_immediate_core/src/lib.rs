@@ -9,12 +9,15 @@ pub use immediate::*;
 pub mod capabilities;
 pub use capabilities::{
     CapSet, ImmCapAccessRequests, ImmCapAccessRequestsResource, ImmCapQueryParam, ImmCapability,
-    ImplCap, ImplCapsEmpty,
+    ImmCapabilityRequires, ImplCap, ImplCapAll, ImplCapsEmpty,
 };
 
 /// Utility types to simplify implementation
 pub mod utils;
 
+/// Attribute macros to reduce boilerplate when implementing capabilities
+pub use bevy_immediate_macros::{capability_list, imm_capability, imm_capability_ext};
+
 /// Required by impl_capability_set macro
 pub use paste;
 
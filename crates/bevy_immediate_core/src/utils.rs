@@ -1,64 +1,138 @@
-use std::marker::PhantomData;
+use std::{
+    any::{Any, TypeId},
+    marker::PhantomData,
+};
 
 use bevy_ecs::world::Mut;
-use type_map::TypeMap;
 
 use crate::{CapSet, ImmEntity, ImmId, imm_id};
 
-/// Wrapper structure around [`TypeMap`] for capabilities to store temporary data.
+/// Structure for capabilities to store temporary data of entity that is being built.
 ///
 /// Wrapper type allows interaction only with data for which users can access Types.
 /// Capabilities can not easily impact each other.
+///
+/// Stores are reused between built entities. Slot of each type keeps its allocation,
+/// so storing values does not allocate once first entities have been built.
+#[derive(Default)]
 pub struct ImmTypeMap {
-    type_map: TypeMap,
+    slots: Vec<(TypeId, Box<dyn TypeSlot>)>,
+}
+
+/// Slot that stores value of single type
+trait TypeSlot: Any + Send {
+    fn clear(&mut self);
+}
+
+impl<T: Send + 'static> TypeSlot for Option<T> {
+    fn clear(&mut self) {
+        *self = None;
+    }
 }
 
 impl ImmTypeMap {
     #[inline]
-    pub(crate) fn new() -> Self {
-        Self {
-            type_map: TypeMap::new(),
-        }
+    fn slot<T: 'static>(&self) -> Option<&Option<T>> {
+        let type_id = TypeId::of::<T>();
+        let (_, slot) = self.slots.iter().find(|(id, _)| *id == type_id)?;
+        (slot.as_ref() as &dyn Any).downcast_ref()
     }
 
-    // pub fn clear(&mut self) {
-    //     self.type_map.clear()
-    // }
-    //
-    /// See [TypeMap::contains]
     #[inline]
-    pub fn contains<T: 'static>(&self) -> bool {
-        self.type_map.contains::<T>()
+    fn slot_mut<T: 'static>(&mut self) -> Option<&mut Option<T>> {
+        let type_id = TypeId::of::<T>();
+        let (_, slot) = self.slots.iter_mut().find(|(id, _)| *id == type_id)?;
+        (slot.as_mut() as &mut dyn Any).downcast_mut()
     }
 
-    /// See [TypeMap::entry]
     #[inline]
-    pub fn entry<T: 'static>(&mut self) -> type_map::Entry<'_, T> {
-        self.type_map.entry()
+    fn slot_or_create<T: Send + 'static>(&mut self) -> &mut Option<T> {
+        let type_id = TypeId::of::<T>();
+        let idx = match self.slots.iter().position(|(id, _)| *id == type_id) {
+            Some(idx) => idx,
+            None => {
+                self.slots.push((type_id, Box::new(None::<T>)));
+                self.slots.len() - 1
+            }
+        };
+        (self.slots[idx].1.as_mut() as &mut dyn Any)
+            .downcast_mut()
+            .expect("Slot is stored under its type id")
     }
 
-    /// See [TypeMap::get]
+    /// Remove all stored values while keeping allocated slots
+    fn clear(&mut self) {
+        for (_, slot) in self.slots.iter_mut() {
+            slot.clear();
+        }
+    }
+
+    /// Is value of type `T` stored
+    #[inline]
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.slot::<T>().is_some_and(Option::is_some)
+    }
+
+    /// Retrieve stored value of type `T`
     #[inline]
     pub fn get<T: 'static>(&self) -> Option<&T> {
-        self.type_map.get()
+        self.slot::<T>()?.as_ref()
     }
 
-    /// See [TypeMap::get_mut]
+    /// Retrieve mutable reference to stored value of type `T`
     #[inline]
     pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
-        self.type_map.get_mut()
+        self.slot_mut::<T>()?.as_mut()
     }
 
-    /// See [TypeMap::insert]
+    /// Retrieve stored value of type `T` or insert value returned by `f`
     #[inline]
-    pub fn insert<T: 'static>(&mut self, val: T) -> Option<T> {
-        self.type_map.insert(val)
+    pub fn get_or_insert_with<T: Send + 'static>(&mut self, f: impl FnOnce() -> T) -> &mut T {
+        self.slot_or_create::<T>().get_or_insert_with(f)
     }
 
-    /// See [TypeMap::remove]
+    /// Store value, returns previously stored value of type `T`
+    #[inline]
+    pub fn insert<T: Send + 'static>(&mut self, val: T) -> Option<T> {
+        self.slot_or_create::<T>().replace(val)
+    }
+
+    /// Remove stored value of type `T`
     #[inline]
     pub fn remove<T: 'static>(&mut self) -> Option<T> {
-        self.type_map.remove()
+        self.slot_mut::<T>()?.take()
+    }
+}
+
+/// [`ImmTypeMap`] for each nesting level of entities that are being built
+///
+/// [`ImmEntity`] values built at the same nesting level can not be alive at the same time,
+/// because each borrows [`crate::Imm`] of that level mutably.
+/// Store of previous entity at the same level is cleared when new entity acquires it.
+#[derive(Default)]
+pub(crate) struct ImmTypeMapStack {
+    maps: Vec<ImmTypeMap>,
+}
+
+impl ImmTypeMapStack {
+    /// Acquire empty store for new entity at given nesting `depth`
+    #[inline]
+    pub(crate) fn acquire(&mut self, depth: usize) -> usize {
+        if self.maps.len() <= depth {
+            self.maps.resize_with(depth + 1, ImmTypeMap::default);
+        }
+        self.maps[depth].clear();
+        depth
+    }
+
+    #[inline]
+    pub(crate) fn get(&self, depth: usize) -> &ImmTypeMap {
+        &self.maps[depth]
+    }
+
+    #[inline]
+    pub(crate) fn get_mut(&mut self, depth: usize) -> &mut ImmTypeMap {
+        &mut self.maps[depth]
     }
 }
 
@@ -66,6 +140,8 @@ impl ImmTypeMap {
 ///
 /// Useful for implementing toggle buttons, menus, dropdowns where
 /// simple local state is enough.
+///
+/// For state that can not be represented by hash use [`ImmEntity::local_state`].
 #[must_use = "You want to store processed value"]
 pub struct ImmLocalHashMemoryHelper<T> {
     key: ImmId,
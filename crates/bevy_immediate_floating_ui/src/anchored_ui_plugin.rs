@@ -1,10 +1,10 @@
 use bevy_ecs::{
     component::Component,
     entity::Entity,
-    hierarchy::Children,
-    query::With,
+    hierarchy::{ChildOf, Children},
+    query::Without,
     schedule::IntoScheduleConfigs,
-    system::{Query, Single},
+    system::{Commands, Query},
 };
 use bevy_math::{
     BVec2, Vec2,
@@ -12,18 +12,21 @@ use bevy_math::{
 };
 use bevy_transform::TransformSystems;
 use bevy_ui::{
-    ComputedNode, ComputedUiRenderTargetInfo, LayoutConfig, Node, UiGlobalTransform, UiSystems,
-    Val, px,
+    ComputedNode, ComputedUiRenderTargetInfo, ComputedUiTargetCamera, LayoutConfig, Node,
+    UiGlobalTransform, UiSystems, UiTargetCamera, Val, px,
 };
-use bevy_window::{PrimaryWindow, Window};
 
-use crate::utils::aabb_overlap;
+use crate::utils::{UiCursorPosition, aabb_overlap};
 
 /// Implements floating UI placement against other entities
 pub struct AnchoredUiPlugin;
 
 impl bevy_app::Plugin for AnchoredUiPlugin {
     fn build(&self, app: &mut bevy_app::App) {
+        app.add_systems(
+            bevy_app::PostUpdate,
+            sync_anchor_target_camera.before(UiSystems::Propagate),
+        );
         app.add_systems(
             bevy_app::PostUpdate,
             position_anchor
@@ -174,6 +177,50 @@ struct PlacementCache {
     last_offset: Option<Vec2>,
 }
 
+/// Camera that was inserted by [`sync_anchor_target_camera`]
+///
+/// Used to detect [`UiTargetCamera`] that was set by user.
+#[derive(Component)]
+struct AnchorSyncedCamera(Entity);
+
+/// Anchored elements without parent are rendered by the same camera as their target entity
+///
+/// [`UiTargetCamera`] that was set explicitly on anchored element is kept.
+#[allow(clippy::type_complexity)]
+fn sync_anchor_target_camera(
+    mut commands: Commands,
+    anchored: Query<
+        (
+            Entity,
+            &AnchorTarget,
+            Option<&UiTargetCamera>,
+            Option<&AnchorSyncedCamera>,
+        ),
+        Without<ChildOf>,
+    >,
+    target_cameras: Query<&ComputedUiTargetCamera>,
+) {
+    for (entity, target, camera, synced) in anchored.iter() {
+        let AnchorTarget::Entity(target) = target else {
+            continue;
+        };
+        let camera = camera.map(UiTargetCamera::entity);
+        if camera.is_some() && camera != synced.map(|synced| synced.0) {
+            // Camera provided by user
+            continue;
+        }
+        let Some(target_camera) = target_cameras.get(*target).ok().and_then(|c| c.get()) else {
+            continue;
+        };
+        if camera != Some(target_camera) {
+            commands.entity(entity).insert((
+                UiTargetCamera(target_camera),
+                AnchorSyncedCamera(target_camera),
+            ));
+        }
+    }
+}
+
 #[allow(clippy::type_complexity)]
 fn position_anchor(
     elements_to_anchor: Query<(
@@ -184,12 +231,13 @@ fn position_anchor(
         &AnchorOption,
         &ComputedNode,
         &ComputedUiRenderTargetInfo,
+        Option<&ComputedUiTargetCamera>,
         &mut Node,
     )>,
     computed_nodes: Query<&ComputedNode>,
     mut global_transform: Query<&mut UiGlobalTransform>,
     children: Query<&Children>,
-    window: Single<&Window, With<PrimaryWindow>>,
+    cursor: UiCursorPosition,
 ) {
     for (
         entity,
@@ -199,12 +247,16 @@ fn position_anchor(
         anchor_option,
         comp_node,
         comp_target_info,
+        target_camera,
         mut node,
     ) in elements_to_anchor
     {
-        let final_position_and_overlap = |flip: BVec2| {
-            let cursor = window.physical_cursor_position();
+        let cursor = match target {
+            AnchorTarget::Cursor => cursor.physical_position(target_camera.and_then(|c| c.get())),
+            _ => None,
+        };
 
+        let final_position_and_overlap = |flip: BVec2| {
             let target_position: Vec2 = match target {
                 AnchorTarget::Entity(entity) => (|| -> _ {
                     let target_compute = computed_nodes.get(*entity).ok()?;
@@ -1,4 +1,11 @@
-use bevy_math::bounding::Aabb2d;
+use bevy_camera::{Camera, RenderTarget};
+use bevy_ecs::{
+    entity::{ContainsEntity, Entity},
+    query::With,
+    system::{Query, SystemParam},
+};
+use bevy_math::{Vec2, bounding::Aabb2d};
+use bevy_window::{PrimaryWindow, Window};
 
 /// Calculates if [`Aabb2d`] is fully inside another [`Aabb2d`]
 pub fn fully_inside(inside: &Aabb2d, outside: &Aabb2d) -> bool {
@@ -16,3 +23,37 @@ pub fn aabb_overlap(a: &Aabb2d, b: &Aabb2d) -> Aabb2d {
         max: a.max.min(b.max),
     }
 }
+
+/// Resolves cursor position inside render target of UI camera
+#[derive(SystemParam)]
+pub struct UiCursorPosition<'w, 's> {
+    cameras: Query<'w, 's, (&'static Camera, &'static RenderTarget)>,
+    windows: Query<'w, 's, &'static Window>,
+    primary_window: Query<'w, 's, Entity, With<PrimaryWindow>>,
+}
+
+impl UiCursorPosition<'_, '_> {
+    /// Cursor position in physical pixels relative to viewport of `camera`.
+    ///
+    /// Primary window is used if camera is not provided.
+    pub fn physical_position(&self, camera: Option<Entity>) -> Option<Vec2> {
+        let primary = self.primary_window.single().ok();
+        let Some(camera) = camera else {
+            return self.windows.get(primary?).ok()?.physical_cursor_position();
+        };
+
+        let (camera, target) = self.cameras.get(camera).ok()?;
+        let RenderTarget::Window(window_ref) = target else {
+            return None;
+        };
+        let window = window_ref.normalize(primary)?.entity();
+        let cursor = self.windows.get(window).ok()?.physical_cursor_position()?;
+
+        let viewport_offset = camera
+            .viewport
+            .as_ref()
+            .map(|viewport| viewport.physical_position.as_vec2())
+            .unwrap_or_default();
+        Some(cursor - viewport_offset)
+    }
+}
@@ -0,0 +1,171 @@
+use proc_macro2::TokenStream;
+use quote::{ToTokens, quote};
+use syn::{
+    Expr, Ident, ItemStruct, Path, Token, Type,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+};
+
+use crate::crate_path;
+
+#[derive(Default)]
+struct CapabilityArgs {
+    read: Vec<Type>,
+    write: Vec<Type>,
+    resource_read: Vec<Type>,
+    resource_write: Vec<Type>,
+    plugins: Vec<PluginArg>,
+    requires: Vec<Type>,
+    build: Option<Path>,
+    krate: Option<Path>,
+}
+
+/// Plugin type with optional value that is added to app
+struct PluginArg {
+    ty: Type,
+    value: Option<Expr>,
+}
+
+impl Parse for PluginArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { ty, value })
+    }
+}
+
+impl PluginArg {
+    fn value(&self) -> syn::Result<TokenStream> {
+        if let Some(value) = &self.value {
+            return Ok(value.to_token_stream());
+        }
+
+        // Unit struct plugin can be constructed from its path
+        if let Type::Path(path) = &self.ty
+            && path.qself.is_none()
+            && path
+                .path
+                .segments
+                .iter()
+                .all(|segment| segment.arguments.is_none())
+        {
+            return Ok(path.to_token_stream());
+        }
+
+        Err(syn::Error::new_spanned(
+            &self.ty,
+            "plugin is not a unit struct, provide value: `Plugin<T> = Plugin::<T>::new()`",
+        ))
+    }
+}
+
+impl Parse for CapabilityArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+
+        while !input.is_empty() {
+            if let Some(krate) = crate_path::parse_crate_arg(input)? {
+                args.krate = Some(krate);
+            } else {
+                let key: Ident = input.parse()?;
+                match key.to_string().as_str() {
+                    "read" => args.read.extend(parse_list(input)?),
+                    "write" => args.write.extend(parse_list(input)?),
+                    "resource_read" => args.resource_read.extend(parse_list(input)?),
+                    "resource_write" => args.resource_write.extend(parse_list(input)?),
+                    "plugins" => args.plugins.extend(parse_list(input)?),
+                    "requires" => args.requires.extend(parse_list(input)?),
+                    "build" => {
+                        input.parse::<Token![=]>()?;
+                        args.build = Some(input.parse()?);
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            key.span(),
+                            "unknown argument, expected one of: read, write, resource_read, \
+                             resource_write, plugins, requires, build, crate",
+                        ));
+                    }
+                }
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(args)
+    }
+}
+
+fn parse_list<T: Parse>(input: ParseStream) -> syn::Result<Punctuated<T, Token![,]>> {
+    let content;
+    syn::parenthesized!(content in input);
+    Punctuated::parse_terminated(&content)
+}
+
+pub fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let args: CapabilityArgs = syn::parse2(attr)?;
+    let item: ItemStruct = syn::parse2(item)?;
+
+    let krate = args.krate.unwrap_or_else(crate_path::core_path);
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = item.generics.split_for_impl();
+
+    let plugin_types = args.plugins.iter().map(|plugin| &plugin.ty);
+    let plugin_values = args
+        .plugins
+        .iter()
+        .map(PluginArg::value)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let read = &args.read;
+    let write = &args.write;
+    let resource_read = &args.resource_read;
+    let resource_write = &args.resource_write;
+    let build = args
+        .build
+        .map(|build| quote! { #build::<Caps>(app, cap_req); });
+
+    let requires = (!args.requires.is_empty()).then(|| {
+        let requires = &args.requires;
+        quote! {
+            impl #impl_generics #krate::ImmCapabilityRequires for #name #ty_generics #where_clause {
+                type Capabilities = (#(#requires,)*);
+            }
+        }
+    });
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics #krate::ImmCapability for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn build<Caps: #krate::CapSet>(
+                app: &mut #krate::bevy_app::App,
+                cap_req: &mut #krate::ImmCapAccessRequests<Caps>,
+            ) {
+                #(
+                    if !app.is_plugin_added::<#plugin_types>() {
+                        app.add_plugins(#plugin_values);
+                    }
+                )*
+
+                #(cap_req.request_component_read::<#read>(app.world_mut());)*
+                #(cap_req.request_component_write::<#write>(app.world_mut());)*
+                #(cap_req.request_resource_read::<#resource_read>(app.world_mut());)*
+                #(cap_req.request_resource_write::<#resource_write>(app.world_mut());)*
+
+                #build
+            }
+        }
+
+        #requires
+    })
+}
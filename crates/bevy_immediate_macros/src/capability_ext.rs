@@ -0,0 +1,184 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    FnArg, GenericParam, ImplItem, ItemImpl, Pat, Path, Signature, Token, Type, Visibility,
+    parse::{Parse, ParseStream},
+};
+
+use crate::crate_path;
+
+struct CapabilityExtArgs {
+    capability: Type,
+    krate: Option<Path>,
+}
+
+impl Parse for CapabilityExtArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let capability = input.parse()?;
+        let mut krate = None;
+
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            krate = crate_path::parse_crate_arg(input)?;
+            if krate.is_none() && !input.is_empty() {
+                return Err(input.error("unknown argument, expected: crate"));
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(Self { capability, krate })
+    }
+}
+
+pub fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let args: CapabilityExtArgs = syn::parse2(attr)?;
+    let item: ItemImpl = syn::parse2(item)?;
+
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "expected `impl TraitName { .. }` block without trait",
+        ));
+    }
+    let trait_name = match &*item.self_ty {
+        Type::Path(path) if path.qself.is_none() => path.path.get_ident(),
+        _ => None,
+    }
+    .ok_or_else(|| syn::Error::new_spanned(&item.self_ty, "expected extension trait name"))?;
+
+    let krate = args.krate.unwrap_or_else(crate_path::core_path);
+    let capability = &args.capability;
+
+    let mut visibility = None;
+    let mut trait_items = Vec::new();
+    let mut impl_items = Vec::new();
+    for impl_item in &item.items {
+        let ImplItem::Fn(method) = impl_item else {
+            return Err(syn::Error::new_spanned(
+                impl_item,
+                "only methods are supported in extension trait",
+            ));
+        };
+
+        let visibility = visibility.get_or_insert_with(|| method.vis.clone());
+        if !same_visibility(visibility, &method.vis) {
+            return Err(syn::Error::new_spanned(
+                &method.sig,
+                "all methods must have the same visibility, it is used for extension trait",
+            ));
+        }
+
+        let (docs, attrs): (Vec<_>, Vec<_>) = method
+            .attrs
+            .iter()
+            .partition(|attr| attr.path().is_ident("doc"));
+        let sig = &method.sig;
+        let block = &method.block;
+        let trait_sig = trait_signature(sig);
+
+        trait_items.push(quote! {
+            #(#docs)*
+            #trait_sig;
+        });
+        impl_items.push(quote! {
+            #(#attrs)*
+            #sig #block
+        });
+    }
+    let visibility = visibility.unwrap_or_else(|| syn::parse_quote!(pub));
+
+    let attrs = &item.attrs;
+    let params = item.generics.params.iter().collect::<Vec<_>>();
+    let param_args = params.iter().map(|param| match param {
+        GenericParam::Lifetime(lifetime) => {
+            let lifetime = &lifetime.lifetime;
+            quote!(#lifetime)
+        }
+        GenericParam::Type(ty) => {
+            let ident = &ty.ident;
+            quote!(#ident)
+        }
+        GenericParam::Const(constant) => {
+            let ident = &constant.ident;
+            quote!(#ident)
+        }
+    });
+    let where_predicates = item
+        .generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| where_clause.predicates.iter())
+        .collect::<Vec<_>>();
+
+    let caps = format_ident!("Caps");
+    let (trait_generics, trait_args) = if params.is_empty() {
+        (quote!(), quote!())
+    } else {
+        (
+            quote!(<#(#params,)* #caps: #krate::CapSet>),
+            quote!(<#(#param_args,)* #caps>),
+        )
+    };
+
+    let declared_lifetime = |name: &str| {
+        item.generics
+            .lifetimes()
+            .find(|lifetime| lifetime.lifetime.ident == name)
+            .map(|lifetime| {
+                let lifetime = &lifetime.lifetime;
+                quote!(#lifetime)
+            })
+            .unwrap_or_else(|| quote!('_))
+    };
+    let lifetime_w = declared_lifetime("w");
+    let lifetime_s = declared_lifetime("s");
+
+    Ok(quote! {
+        #(#attrs)*
+        #visibility trait #trait_name #trait_generics
+        where #(#where_predicates,)*
+        {
+            #(#trait_items)*
+        }
+
+        impl<#(#params,)* #caps> #trait_name #trait_args
+            for #krate::ImmEntity<'_, #lifetime_w, #lifetime_s, #caps>
+        where
+            #caps: #krate::ImplCap<#capability>,
+            #(#where_predicates,)*
+        {
+            #(#impl_items)*
+        }
+    })
+}
+
+/// Signature without argument patterns that are not allowed in functions without body
+fn trait_signature(sig: &Signature) -> Signature {
+    let mut sig = sig.clone();
+    for (idx, input) in sig.inputs.iter_mut().enumerate() {
+        match input {
+            FnArg::Receiver(receiver) => {
+                if receiver.reference.is_none() {
+                    receiver.mutability = None;
+                }
+            }
+            FnArg::Typed(typed) => match &mut *typed.pat {
+                Pat::Ident(ident) if ident.subpat.is_none() => {
+                    ident.by_ref = None;
+                    ident.mutability = None;
+                }
+                pat => {
+                    let ident = format_ident!("arg{idx}");
+                    *pat = syn::parse_quote!(#ident);
+                }
+            },
+        }
+    }
+    sig
+}
+
+fn same_visibility(a: &Visibility, b: &Visibility) -> bool {
+    quote!(#a).to_string() == quote!(#b).to_string()
+}
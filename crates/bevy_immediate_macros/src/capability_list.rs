@@ -0,0 +1,176 @@
+use proc_macro2::{Punct, Spacing, TokenStream, TokenTree};
+use quote::{ToTokens, quote};
+use syn::{
+    Attribute, Ident, Path, Token, Type,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+};
+
+use crate::crate_path;
+
+enum CapabilityListInput {
+    /// `name = (A, B)` or `name = base! + (A, B)`
+    Define {
+        attrs: Vec<Attribute>,
+        name: Ident,
+        base: Option<Path>,
+        capabilities: Vec<Type>,
+    },
+    /// Callback from base list macro: `@extend name (A, B) [Base1, Base2]`
+    Extend {
+        attrs: Vec<Attribute>,
+        name: Ident,
+        capabilities: Vec<TokenStream>,
+        base_capabilities: Vec<TokenStream>,
+    },
+}
+
+impl Parse for CapabilityListInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![@]) {
+            input.parse::<Token![@]>()?;
+            let keyword: Ident = input.parse()?;
+            if keyword != "extend" {
+                return Err(syn::Error::new(keyword.span(), "expected `extend`"));
+            }
+            let attrs = Attribute::parse_outer(input)?;
+            let name = input.parse()?;
+
+            let capabilities;
+            syn::parenthesized!(capabilities in input);
+            let base_capabilities;
+            syn::bracketed!(base_capabilities in input);
+
+            return Ok(Self::Extend {
+                attrs,
+                name,
+                capabilities: split_list(&capabilities)?,
+                base_capabilities: split_list(&base_capabilities)?,
+            });
+        }
+
+        let attrs = Attribute::parse_outer(input)?;
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+
+        let mut base = None;
+        if !input.peek(syn::token::Paren) {
+            base = Some(input.parse()?);
+            input.parse::<Token![!]>()?;
+        }
+
+        let mut capabilities = Vec::new();
+        if base.is_none() || input.peek(Token![+]) {
+            if base.is_some() {
+                input.parse::<Token![+]>()?;
+            }
+            let content;
+            syn::parenthesized!(content in input);
+            capabilities.extend(Punctuated::<Type, Token![,]>::parse_terminated(&content)?);
+        }
+
+        Ok(Self::Define {
+            attrs,
+            name,
+            base,
+            capabilities,
+        })
+    }
+}
+
+/// Split comma separated list of types without parsing them
+///
+/// Types forwarded from another list macro may start with `$crate`.
+fn split_list(input: ParseStream) -> syn::Result<Vec<TokenStream>> {
+    let mut items = Vec::new();
+    let mut current = TokenStream::new();
+    let mut depth = 0usize;
+
+    while !input.is_empty() {
+        let token: TokenTree = input.parse()?;
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    items.push(std::mem::take(&mut current));
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        current.extend([token]);
+    }
+    if !current.is_empty() {
+        items.push(current);
+    }
+
+    Ok(items)
+}
+
+/// Types starting with `crate::` are resolved relative to the crate that defines list
+fn crate_relative(ty: &Type) -> TokenStream {
+    let mut tokens = ty.to_token_stream().into_iter().peekable();
+    let mut output = TokenStream::new();
+    if let Some(TokenTree::Ident(ident)) = tokens.peek()
+        && ident == "crate"
+    {
+        output.extend([TokenTree::Punct(Punct::new('$', Spacing::Alone))]);
+    }
+    output.extend(tokens);
+    output
+}
+
+fn list_macro(attrs: &[Attribute], name: &Ident, capabilities: &[TokenStream]) -> TokenStream {
+    quote! {
+        #(#attrs)*
+        macro_rules! #name {
+            ([$($callback:tt)*] $($args:tt)*) => {
+                $($callback)*! { $($args)* [#(#capabilities),*] }
+            };
+        }
+    }
+}
+
+pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let input: CapabilityListInput = syn::parse2(input)?;
+
+    Ok(match input {
+        CapabilityListInput::Define {
+            attrs,
+            name,
+            base: None,
+            capabilities,
+        } => {
+            let capabilities = capabilities.iter().map(crate_relative).collect::<Vec<_>>();
+            list_macro(&attrs, &name, &capabilities)
+        }
+        CapabilityListInput::Define {
+            attrs,
+            name,
+            base: Some(base),
+            capabilities,
+        } => {
+            let krate = crate_path::core_path();
+            let capabilities = capabilities.iter().map(crate_relative);
+            quote! {
+                #base! {
+                    [#krate::capability_list]
+                    @extend #(#attrs)* #name (#(#capabilities),*)
+                }
+            }
+        }
+        CapabilityListInput::Extend {
+            attrs,
+            name,
+            capabilities,
+            base_capabilities,
+        } => {
+            let all = base_capabilities
+                .into_iter()
+                .chain(capabilities)
+                .collect::<Vec<_>>();
+            list_macro(&attrs, &name, &all)
+        }
+    })
+}
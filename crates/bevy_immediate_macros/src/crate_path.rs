@@ -0,0 +1,40 @@
+use proc_macro_crate::{FoundCrate, crate_name};
+use proc_macro2::Span;
+use syn::{Ident, Path, Token, parse::ParseStream, parse_quote};
+
+/// Path to `bevy_immediate_core` crate from the crate that invokes macro
+///
+/// Dependency is looked up in invoking crate manifest with `proc-macro-crate`,
+/// so renamed and workspace inherited dependencies are supported.
+/// Crates that depend only on `bevy_immediate` access it through reexport.
+/// Use `crate = path` argument when neither is a direct dependency.
+pub fn core_path() -> Path {
+    if let Ok(found) = crate_name("bevy_immediate_core") {
+        return match found {
+            FoundCrate::Itself => parse_quote!(crate),
+            FoundCrate::Name(name) => {
+                let name = Ident::new(&name, Span::call_site());
+                parse_quote!(::#name)
+            }
+        };
+    }
+
+    match crate_name("bevy_immediate") {
+        Ok(FoundCrate::Name(name)) => {
+            let name = Ident::new(&name, Span::call_site());
+            parse_quote!(::#name::bevy_immediate_core)
+        }
+        Ok(FoundCrate::Itself) => parse_quote!(crate::bevy_immediate_core),
+        Err(_) => parse_quote!(::bevy_immediate_core),
+    }
+}
+
+/// Parse `crate = path` argument if it is next in input
+pub fn parse_crate_arg(input: ParseStream) -> syn::Result<Option<Path>> {
+    if !input.peek(Token![crate]) {
+        return Ok(None);
+    }
+    input.parse::<Token![crate]>()?;
+    input.parse::<Token![=]>()?;
+    Ok(Some(Path::parse_mod_style(input)?))
+}
@@ -0,0 +1,115 @@
+#![warn(missing_docs)]
+#![doc = include_str!("../README.md")]
+
+use proc_macro::TokenStream;
+
+mod capability;
+mod capability_ext;
+mod capability_list;
+mod crate_path;
+
+/// Implements `ImmCapability` for unit struct
+///
+/// Generated `build` function adds listed plugins (if they are not added yet)
+/// and requests listed component and resource access.
+///
+/// Supported arguments:
+/// * `read(..)`, `write(..)` - components accessed on immediate mode entities
+/// * `resource_read(..)`, `resource_write(..)` - resources accessed during immediate mode
+/// * `plugins(..)` - plugins required by capability. Unit struct plugins can be listed by name,
+///   others require value: `plugins(MyPlugin<T> = MyPlugin::<T>::new())`
+/// * `requires(..)` - capabilities that must be present in the same capability set.
+///   See `ImmCapabilityRequires`
+/// * `build = path` - additional build function `fn build<Caps: CapSet>(&mut App, &mut ImmCapAccessRequests<Caps>)`
+/// * `crate = path` - path to `bevy_immediate_core`. By default it is found in dependencies
+///   of invoking crate, directly or through `bevy_immediate` reexport
+///
+/// ```
+/// # use bevy_ecs::{component::Component, resource::Resource};
+/// # use bevy_immediate_core::imm_capability;
+/// # #[derive(Component)] struct Hovered;
+/// # #[derive(Component)] struct Node;
+/// # #[derive(Component)] struct BackgroundColor;
+/// # #[derive(Resource)] struct InputFocus;
+/// # struct TrackClickedPlugin;
+/// # impl bevy_app::Plugin for TrackClickedPlugin { fn build(&self, _: &mut bevy_app::App) {} }
+/// #[imm_capability(
+///     read(Hovered),
+///     write(Node, BackgroundColor),
+///     resource_read(InputFocus),
+///     plugins(TrackClickedPlugin),
+/// )]
+/// pub struct CapabilityUiMy;
+/// ```
+#[proc_macro_attribute]
+pub fn imm_capability(attr: TokenStream, item: TokenStream) -> TokenStream {
+    capability::expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Defines extension trait for `ImmEntity` from method list
+///
+/// Trait is implemented for every capability set that implements given capability.
+/// Trait name is taken from `impl` block. Method doc comments are moved to trait definition.
+///
+/// If `impl` block declares generic parameters, trait is made generic over them and
+/// capability set `Caps`. Lifetimes `'w` and `'s` are used as `ImmEntity` lifetimes, so
+/// methods can accept closures with `Imm<'w, 's, Caps>`.
+///
+/// Supported arguments: capability type, `crate = path` (see [`macro@imm_capability`]).
+///
+/// ```
+/// # use bevy_ecs::component::Component;
+/// # use bevy_immediate_core::{imm_capability, imm_capability_ext};
+/// # #[derive(Component)] struct Hovered(bool);
+/// # impl Hovered { fn get(&self) -> bool { self.0 } }
+/// # #[imm_capability(read(Hovered))]
+/// # pub struct CapabilityUiMy;
+/// /// Implements support for my capability
+/// #[imm_capability_ext(CapabilityUiMy)]
+/// impl ImmUiMy {
+///     /// Entity is hovered
+///     pub fn my_hovered(&mut self) -> bool {
+///         matches!(self.cap_get_component::<Hovered>(), Ok(Some(hovered)) if hovered.get())
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn imm_capability_ext(attr: TokenStream, item: TokenStream) -> TokenStream {
+    capability_ext::expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Defines named list of capabilities as `macro_rules` macro
+///
+/// List can be used to define capability sets with `impl_capability_set!`
+/// and to define larger lists. Use `crate::` prefixed or fully qualified capability paths,
+/// so that list can be used from other modules and crates.
+/// Add `#[macro_export]` to make list available to other crates.
+///
+/// ```
+/// # use bevy_immediate_core::{ImplCapsEmpty, capability_list, impl_capability_set, imm_capability};
+/// # #[imm_capability] pub struct CapabilityUiMy;
+/// # #[imm_capability] pub struct CapabilityUiOther;
+/// # #[imm_capability] pub struct CapabilityUiExtra;
+/// # pub struct CapsMyUi;
+/// capability_list!(
+///     #[macro_export]
+///     /// Capabilities for my UI
+///     caps_my_ui = (crate::CapabilityUiMy, crate::CapabilityUiOther)
+/// );
+///
+/// // Extends existing list
+/// capability_list!(caps_my_ui_extra = caps_my_ui! + (crate::CapabilityUiExtra));
+///
+/// impl_capability_set!(CapsMyUi, ImplCapsMyUi > ImplCapsEmpty, caps_my_ui_extra!);
+/// # fn main() {}
+/// ```
+#[proc_macro]
+pub fn capability_list(input: TokenStream) -> TokenStream {
+    capability_list::expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
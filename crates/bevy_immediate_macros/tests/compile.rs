@@ -0,0 +1,8 @@
+//! Compile tests for capability macros
+
+#[test]
+fn capability_macros() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/compile/pass/*.rs");
+    cases.compile_fail("tests/compile/fail/*.rs");
+}
@@ -0,0 +1,17 @@
+use bevy_immediate_core::{imm_capability, imm_capability_ext};
+
+#[imm_capability(reads(u32))]
+pub struct CapabilityUnknownArgument;
+
+struct GenericPlugin<T>(T);
+
+#[imm_capability(plugins(GenericPlugin<u32>))]
+pub struct CapabilityPluginWithoutValue;
+
+#[imm_capability]
+pub struct CapabilityValid;
+
+#[imm_capability_ext(CapabilityValid)]
+impl Clone for ImmValid {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use bevy_immediate_core::{ImplCapsEmpty, impl_capability_set, imm_capability};
+
+#[imm_capability]
+pub struct CapabilityBase;
+
+#[imm_capability(requires(CapabilityBase))]
+pub struct CapabilityDependent;
+
+pub struct CapsMissing;
+impl_capability_set!(CapsMissing, ImplCapsMissing > ImplCapsEmpty, (CapabilityDependent));
+
+fn main() {}
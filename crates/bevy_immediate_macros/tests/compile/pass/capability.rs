@@ -0,0 +1,111 @@
+use bevy_ecs::{component::Component, resource::Resource};
+use bevy_immediate_core::{
+    CapSet, ImmCapAccessRequests, ImmEntity, ImplCapsEmpty, capability_list,
+    impl_capability_set, imm_capability, imm_capability_ext,
+};
+
+#[derive(Component)]
+struct Speed(f32);
+
+#[derive(Component)]
+struct Label;
+
+#[derive(Resource)]
+struct Settings;
+
+#[derive(Resource)]
+struct Counter;
+
+struct CounterPlugin;
+
+impl bevy_app::Plugin for CounterPlugin {
+    fn build(&self, app: &mut bevy_app::App) {
+        app.insert_resource(Counter);
+    }
+}
+
+struct ValuePlugin<T>(T);
+
+impl<T: Send + Sync + 'static> bevy_app::Plugin for ValuePlugin<T> {
+    fn build(&self, _app: &mut bevy_app::App) {}
+}
+
+fn extra_build<Caps: CapSet>(app: &mut bevy_app::App, cap_req: &mut ImmCapAccessRequests<Caps>) {
+    let _ = (app, cap_req);
+}
+
+#[imm_capability(
+    read(Label),
+    write(Speed),
+    resource_read(Settings),
+    resource_write(Counter),
+    plugins(CounterPlugin, ValuePlugin<u32> = ValuePlugin(0u32)),
+    build = extra_build,
+)]
+pub struct CapabilitySpeed;
+
+#[imm_capability(requires(CapabilitySpeed))]
+pub struct CapabilityFaster;
+
+mod alias {
+    pub use bevy_immediate_core as core;
+}
+
+#[imm_capability(crate = alias::core)]
+pub struct CapabilityRenamed;
+
+/// Extension with receiver and argument patterns that are not allowed in trait declaration
+#[imm_capability_ext(CapabilitySpeed)]
+impl ImmSpeed {
+    /// Current speed
+    pub fn speed(&mut self) -> Option<f32> {
+        match self.cap_get_component::<Speed>() {
+            Ok(Some(speed)) => Some(speed.0),
+            _ => None,
+        }
+    }
+
+    /// Set speed
+    pub fn with_speed(mut self, mut speed: f32) -> Self {
+        speed = speed.max(0.);
+        self.entity_commands().insert(Speed(speed));
+        self
+    }
+
+    /// Set speed from pair
+    pub fn with_speed_pair(self, (a, b): (f32, f32)) -> Self {
+        self.with_speed(a + b)
+    }
+}
+
+/// Generic extension that accepts closures with `Imm`
+#[imm_capability_ext(CapabilityFaster, crate = alias::core)]
+impl<'w, 's, T: Clone> ImmFaster {
+    /// Build child for value
+    pub fn faster_child(
+        &mut self,
+        value: T,
+        f: impl FnOnce(&mut bevy_immediate_core::Imm<'w, 's, Caps>, T),
+    ) -> &mut Self {
+        let _ = (value, f);
+        self
+    }
+}
+
+capability_list!(caps_speed = (crate::CapabilitySpeed, crate::CapabilityFaster));
+capability_list!(caps_all = caps_speed! + (crate::CapabilityRenamed));
+
+pub struct CapsSpeed;
+impl_capability_set!(CapsSpeed, ImplCapsSpeed > ImplCapsEmpty, caps_speed!);
+
+pub struct CapsAll;
+impl_capability_set!(CapsAll, ImplCapsAll > ImplCapsSpeed, caps_all!);
+
+fn use_extension<Caps: ImplCapsSpeed>(entity: ImmEntity<'_, '_, '_, Caps>) {
+    let mut entity = entity.with_speed(1.).with_speed_pair((1., 2.));
+    let _ = entity.speed();
+}
+
+fn main() {
+    let _ = use_extension::<CapsAll>;
+}
@@ -11,7 +11,9 @@ use crate::{
     interaction::{CapabilityUiInteraction, ImmUiInteraction},
     tooltip_plugin::{TooltipGlobalState, TooltipPlugin, TooltipSource},
 };
-use bevy_immediate_core::{CapSet, Imm, ImmCapAccessRequests, ImmCapability, ImmEntity, ImplCap};
+use bevy_immediate_core::{
+    CapSet, Imm, ImmCapAccessRequests, ImmCapability, ImmCapabilityRequires, ImmEntity, ImplCap,
+};
 
 /// Implements capability to create floatable, anchored elements
 pub struct CapabilityUiAnchored;
@@ -42,6 +44,10 @@ impl ImmCapability for CapabilityUiAnchored {
     }
 }
 
+impl ImmCapabilityRequires for CapabilityUiAnchored {
+    type Capabilities = (CapabilityUiInteraction,);
+}
+
 /// Implements logic to create floating anchored elements
 pub trait ImmUiAnchored<'w, 's, Caps: CapSet> {
     /// Show tooltip with given content
@@ -1,17 +1,72 @@
-use bevy_ecs::schedule::IntoScheduleConfigs;
+use bevy_app::MainScheduleOrder;
+use bevy_ecs::schedule::{InternedScheduleLabel, IntoScheduleConfigs, ScheduleLabel};
+use bevy_ui::UiTargetCamera;
 
-use bevy_immediate_core::{CapSet, ImmCapAccessRequests, ImmCapability, ImmediateSystemSet};
+use bevy_immediate_core::{
+    CapSet, ImmCapAccessRequests, ImmCapability, ImmEntity, ImmId, ImmSchedules, ImmediateSystemSet,
+};
+
+use crate::retention::RetainedHiddenPlugin;
 
 /// Base capability for UI that sets up correct order of immediate system execution
 pub struct CapabilityUiBase;
 
 impl ImmCapability for CapabilityUiBase {
     fn build<Cap: CapSet>(app: &mut bevy_app::App, cap_req: &mut ImmCapAccessRequests<Cap>) {
-        app.configure_sets(
-            bevy_app::PostUpdate,
-            ImmediateSystemSet::<Cap>::default().before(bevy_ui::UiSystems::Prepare),
-        );
+        // Build systems are ordered before `ImmediateSystemSet`.
+        // UI must be built and upkept before layout is computed in `PostUpdate`.
+        let schedules = app.world().resource::<ImmSchedules<Cap>>();
+        let (build, upkeep) = (schedules.build(), schedules.upkeep());
+        let post_update = bevy_app::PostUpdate.intern();
+        for schedule in [build, upkeep] {
+            assert!(
+                !runs_after(app, schedule, post_update),
+                "Immediate mode UI schedule {schedule:?} runs after PostUpdate, \
+                 entity trees would be laid out only in next frame. \
+                 Use schedule that runs before or is PostUpdate."
+            );
+        }
+        // Other schedules run before `PostUpdate`, so UI is already built when layout is computed
+        if build == post_update || upkeep == post_update {
+            app.configure_sets(
+                post_update,
+                ImmediateSystemSet::<Cap>::default().before(bevy_ui::UiSystems::Prepare),
+            );
+        }
+
+        if !app.is_plugin_added::<RetainedHiddenPlugin>() {
+            app.add_plugins(RetainedHiddenPlugin);
+        }
+
+        cap_req.add_on_children_event_listener(Box::new(insert_root_target_camera::<Cap>));
+    }
+}
+
+/// `schedule` is executed after `other` in [`MainScheduleOrder`]
+fn runs_after(
+    app: &bevy_app::App,
+    schedule: InternedScheduleLabel,
+    other: InternedScheduleLabel,
+) -> bool {
+    let Some(order) = app.world().get_resource::<MainScheduleOrder>() else {
+        return false;
+    };
+    let position = |label| order.labels.iter().position(|l| *l == label);
+    matches!((position(schedule), position(other)), (Some(a), Some(b)) if a > b)
+}
+
+/// Top level entities of entity trees built with `build_immediate_root_for`
+/// are rendered by target camera
+fn insert_root_target_camera<Cap: CapSet>(entity: &mut ImmEntity<Cap>) {
+    if entity.parent_entity().is_some() {
+        return;
+    }
+    let Some(camera) = entity.root_target() else {
+        return;
+    };
 
-        let _ = cap_req;
+    struct RootTargetCamera;
+    if entity.hash_set_typ::<RootTargetCamera>(ImmId::new(camera)) {
+        entity.entity_commands().insert(UiTargetCamera(camera));
     }
 }
@@ -10,64 +10,47 @@ use bevy_picking::{
 };
 use bevy_platform::collections::HashMap;
 
-use bevy_immediate_core::{CapSet, ImmCapAccessRequests, ImmCapability, ImmEntity, ImplCap};
+use bevy_immediate_core::{imm_capability, imm_capability_ext};
 
 /// Immediate mode capability for pointer related events
+#[imm_capability(
+    read(TrackClicked),
+    resource_read(TrackClickedEntitiesResource),
+    plugins(TrackClickedPlugin)
+)]
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CapabilityUiClicked;
 
-impl ImmCapability for CapabilityUiClicked {
-    fn build<CM: CapSet>(app: &mut bevy_app::App, cap_req: &mut ImmCapAccessRequests<CM>) {
-        if !app.is_plugin_added::<TrackClickedPlugin>() {
-            app.add_plugins(TrackClickedPlugin);
-        }
-
-        cap_req.request_component_read::<TrackClicked>(app.world_mut());
-        cap_req.request_resource_read::<TrackClickedEntitiesResource>(app.world_mut());
-    }
-}
-
 /// Implements support for pointer related logic
-pub trait ImmUiClicked {
+#[imm_capability_ext(CapabilityUiClicked)]
+impl ImmUiClicked {
     /// Entity clicked during last frame
-    fn clicked(&mut self) -> bool;
-    /// Primary button clicked
-    fn primary_clicked(&mut self) -> bool;
-    /// Secondary button clicked
-    fn secondary_clicked(&mut self) -> bool;
-    /// Middle button clicked
-    fn middle_clicked(&mut self) -> bool;
-    /// Pointer button that was used to click this entity
-    fn clicked_by(&mut self) -> Option<PointerButton>;
-    /// Access reference to stored pointer click event
-    fn pointer_click(&mut self) -> Option<&Pointer<Click>>;
-}
-
-impl<Cap: CapSet> ImmUiClicked for ImmEntity<'_, '_, '_, Cap>
-where
-    Cap: ImplCap<CapabilityUiClicked>,
-{
-    fn clicked(&mut self) -> bool {
+    pub fn clicked(&mut self) -> bool {
         self.pointer_click().is_some()
     }
 
-    fn primary_clicked(&mut self) -> bool {
+    /// Primary button clicked
+    pub fn primary_clicked(&mut self) -> bool {
         self.clicked_by() == Some(PointerButton::Primary)
     }
 
-    fn secondary_clicked(&mut self) -> bool {
+    /// Secondary button clicked
+    pub fn secondary_clicked(&mut self) -> bool {
         self.clicked_by() == Some(PointerButton::Secondary)
     }
 
-    fn middle_clicked(&mut self) -> bool {
+    /// Middle button clicked
+    pub fn middle_clicked(&mut self) -> bool {
         self.clicked_by() == Some(PointerButton::Middle)
     }
 
-    fn clicked_by(&mut self) -> Option<PointerButton> {
+    /// Pointer button that was used to click this entity
+    pub fn clicked_by(&mut self) -> Option<PointerButton> {
         self.pointer_click().map(|event| event.button)
     }
 
-    fn pointer_click(&mut self) -> Option<&Pointer<Click>> {
+    /// Access reference to stored pointer click event
+    pub fn pointer_click(&mut self) -> Option<&Pointer<Click>> {
         'correct: {
             if !self.cap_entity_contains::<TrackClicked>() {
                 break 'correct;
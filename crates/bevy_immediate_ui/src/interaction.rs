@@ -1,32 +1,17 @@
 use bevy_picking::hover::Hovered;
 use bevy_ui::Pressed;
 
-use bevy_immediate_core::{CapSet, ImmCapAccessRequests, ImmCapability, ImmEntity, ImplCap};
+use bevy_immediate_core::{imm_capability, imm_capability_ext};
 
 /// Capability for long lasting interaction tracking from immediate UI
+#[imm_capability(read(Hovered))]
 pub struct CapabilityUiInteraction;
 
-impl ImmCapability for CapabilityUiInteraction {
-    fn build<Cap: CapSet>(app: &mut bevy_app::App, cap_req: &mut ImmCapAccessRequests<Cap>) {
-        cap_req.request_component_read::<Hovered>(app.world_mut());
-    }
-}
-
 /// Implements support for hovered, clicked effects
-pub trait ImmUiInteraction {
-    /// Checks state from [`Hovered`]
-    fn hovered(&mut self) -> bool;
-
-    /// Checks if component has [`Pressed`]
-    fn pressed(&self) -> bool;
-}
-
-impl<Cap: CapSet> ImmUiInteraction for ImmEntity<'_, '_, '_, Cap>
-where
-    Cap: ImplCap<CapabilityUiInteraction>,
-{
+#[imm_capability_ext(CapabilityUiInteraction)]
+impl ImmUiInteraction {
     /// Check if entity is hovered. Logic reads [`bevy_picking::hover::Hovered`]
-    fn hovered(&mut self) -> bool {
+    pub fn hovered(&mut self) -> bool {
         let Ok(Some(hovered)) = self.cap_get_component::<Hovered>() else {
             self.entity_commands().insert(Hovered::default());
             return false;
@@ -38,7 +23,7 @@ where
     ///
     /// To insert, remove [`Pressed`] component to entity
     /// use similar logic as in [`bevy_ui_widgets::ButtonPlugin`]
-    fn pressed(&self) -> bool {
+    pub fn pressed(&self) -> bool {
         let Ok(entity) = self.cap_get_entity() else {
             return false;
         };
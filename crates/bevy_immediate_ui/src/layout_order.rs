@@ -5,12 +5,13 @@ use bevy_ecs::{
     hierarchy::Children,
     resource::Resource,
     schedule::IntoScheduleConfigs,
-    system::{Query, ResMut},
+    system::{Local, Query, ResMut},
 };
 use bevy_platform::collections::HashMap;
 
 use bevy_immediate_core::{
-    CapSet, ImmCapAccessRequests, ImmCapability, ImmEntity, ImmediateSystemSet,
+    CapSet, ImmCapAccessRequests, ImmCapability, ImmEntity, ImmFrameStats, ImmSchedules,
+    ImmediateSystemSet, imm_rebuild_needed,
 };
 
 /// Base capability for UI that sets up correct order of immediate system execution
@@ -19,10 +20,12 @@ pub struct CapabilityUiLayoutOrder;
 impl ImmCapability for CapabilityUiLayoutOrder {
     fn build<Cap: CapSet>(app: &mut bevy_app::App, cap_req: &mut ImmCapAccessRequests<Cap>) {
         app.insert_resource(UiOrderTracker::<Cap>::default());
+        let upkeep = app.world().resource::<ImmSchedules<Cap>>().upkeep();
         app.add_systems(
-            bevy_app::PostUpdate,
+            upkeep,
             immediate_mode_ui_children_order_system::<Cap>
-                .in_set(ImmediateSystemSet::<Cap>::default()),
+                .in_set(ImmediateSystemSet::<Cap>::default())
+                .run_if(imm_rebuild_needed::<Cap>),
         );
 
         cap_req.request_resource_write::<UiOrderTracker<Cap>>(app.world_mut());
@@ -45,6 +48,19 @@ fn add_children_to_ui_order_tracker<Cap: CapSet>(entity: &mut ImmEntity<Cap>) {
     *value += 1;
 }
 
+/// Reused buffers for children ordering
+#[derive(Default)]
+struct OrderBuffers {
+    /// Creation order and slot index of immediate mode managed children
+    managed: Vec<(usize, usize)>,
+    /// Positions in [`Children`] occupied by immediate mode managed children
+    slots: Vec<usize>,
+    /// Slot index where managed child with given rank is currently placed
+    rank_slot: Vec<usize>,
+    /// Rank of managed child currently placed in slot
+    slot_rank: Vec<usize>,
+}
+
 fn immediate_mode_ui_children_order_system<Cap: CapSet>(
     mut tracker: ResMut<UiOrderTracker<Cap>>,
 
@@ -52,13 +68,16 @@ fn immediate_mode_ui_children_order_system<Cap: CapSet>(
     // in case of widgets parent entity could be
     // entity that was not created in immediate mode
     mut query: Query<&mut Children>,
+    mut buffers: Local<OrderBuffers>,
+    mut stats: ResMut<ImmFrameStats<Cap>>,
 ) {
     let tracker = tracker.deref_mut();
-
-    let mut for_sort = Vec::new();
-    let mut idx_to_location = Vec::new();
-    let mut location_to_idx = Vec::new();
-    let mut next_locations = Vec::new();
+    let OrderBuffers {
+        managed,
+        slots,
+        rank_slot,
+        slot_rank,
+    } = buffers.deref_mut();
 
     for (&parent_entity, &child_count) in tracker.parent_entities.iter() {
         if child_count <= 1 {
@@ -72,64 +91,59 @@ fn immediate_mode_ui_children_order_system<Cap: CapSet>(
             continue;
         };
 
-        // Collect all childrens for sorting
-        for_sort.reserve(children.len());
-        next_locations.reserve(children.len());
-
         // We need to extract subset of entities
-        // that are managed by immediate mode and which needs sorting
-        let mut count_order = 0;
-        let mut matches = true;
+        // that are managed by immediate mode and which needs sorting.
+        // Other children keep their positions.
+        let mut sorted = true;
         for (child_idx, child) in children.iter().enumerate() {
             let Some(&child_order) = tracker.children_order.get(child) else {
                 continue;
             };
-            if child_order != count_order {
-                matches = false;
+            if managed
+                .last()
+                .is_some_and(|&(order, _)| order > child_order)
+            {
+                sorted = false;
             }
-
-            count_order += 1;
-            for_sort.push((child_order, child_idx));
-            next_locations.push(child_idx);
+            managed.push((child_order, slots.len()));
+            slots.push(child_idx);
         }
 
-        if !matches {
-            // Needs sorting
+        if !sorted {
+            stats.current_mut().layout_order_sorts += 1;
 
-            idx_to_location.reserve(children.len());
-            location_to_idx.reserve(children.len());
-            for child_idx in 0..children.len() {
-                idx_to_location.push(child_idx);
-                location_to_idx.push(child_idx);
+            // After sorting managed child with rank `k` must be placed into `slots[k]`
+            managed.sort_unstable();
+
+            rank_slot.extend(managed.iter().map(|&(_, slot)| slot));
+            slot_rank.resize(slots.len(), 0);
+            for (rank, &slot) in rank_slot.iter().enumerate() {
+                slot_rank[slot] = rank;
             }
 
+            // Children that are already in place are not touched.
+            // Every swap places at least one child into its final position.
             let children = children.deref_mut();
-            for_sort.sort_unstable();
-            next_locations.reverse();
-
-            for &(_, orig_idx) in for_sort.iter() {
-                let orig_idx_loc = idx_to_location[orig_idx]; // Retrieve real position
-                let nxt_loc = next_locations.pop().unwrap();
-
-                if orig_idx_loc == nxt_loc {
-                    continue; // Already at the right place
+            for rank in 0..rank_slot.len() {
+                let slot = rank_slot[rank];
+                if slot == rank {
+                    continue;
                 }
 
-                // Simulate swaps in lookup tables
-                let swapped_away = location_to_idx[nxt_loc];
+                let displaced_rank = slot_rank[rank];
+                children.swap(slots[rank], slots[slot]);
 
-                children.swap(orig_idx_loc, nxt_loc);
-                location_to_idx.swap(orig_idx_loc, nxt_loc);
-
-                idx_to_location[orig_idx] = nxt_loc;
-                idx_to_location[swapped_away] = orig_idx_loc;
+                slot_rank[slot] = displaced_rank;
+                rank_slot[displaced_rank] = slot;
+                slot_rank[rank] = rank;
+                rank_slot[rank] = rank;
             }
         }
 
-        for_sort.clear();
-        idx_to_location.clear();
-        location_to_idx.clear();
-        next_locations.clear();
+        managed.clear();
+        slots.clear();
+        rank_slot.clear();
+        slot_rank.clear();
     }
 
     tracker.parent_entities.clear();
@@ -167,3 +181,67 @@ impl<Cap: CapSet> Default for UiOrderTracker<Cap> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_immediate_core::{
+        ImmFrameStats, ImplCapsEmpty, impl_capability_set, testing::ImmTestApp,
+    };
+
+    use super::CapabilityUiLayoutOrder;
+
+    struct CapsTest;
+    impl_capability_set!(
+        CapsTest,
+        ImplCapsTest > ImplCapsEmpty,
+        (CapabilityUiLayoutOrder)
+    );
+
+    #[test]
+    fn children_are_reordered_to_build_order() {
+        let mut test = ImmTestApp::<CapsTest>::new();
+        let build = |keys: [&'static str; 3]| {
+            move |ui: &mut bevy_immediate_core::Imm<CapsTest>| {
+                ui.ch_id("parent")
+                    .add(|ui| {
+                        for key in keys {
+                            ui.ch_id(key);
+                        }
+                    })
+                    .imm_id()
+            }
+        };
+
+        let parent_id = test.run_frame(build(["a", "b", "c"]));
+        let parent = test.entity(parent_id).unwrap();
+        let ids = test.children_ids(parent);
+
+        // Entity that is not managed by immediate mode keeps its position
+        let unmanaged = test.world_mut().spawn_empty().id();
+        test.world_mut()
+            .entity_mut(parent)
+            .insert_children(1, &[unmanaged]);
+
+        test.run_frame(build(["c", "a", "b"]));
+        assert_eq!(test.children_ids(parent), [ids[2], ids[0], ids[1]]);
+        assert_eq!(test.children(parent)[1], unmanaged);
+        assert_eq!(
+            test.world()
+                .resource::<ImmFrameStats<CapsTest>>()
+                .last_frame()
+                .layout_order_sorts,
+            1
+        );
+
+        // Already sorted children are not touched
+        test.run_frame(build(["c", "a", "b"]));
+        assert_eq!(test.children_ids(parent), [ids[2], ids[0], ids[1]]);
+        assert_eq!(
+            test.world()
+                .resource::<ImmFrameStats<CapsTest>>()
+                .last_frame()
+                .layout_order_sorts,
+            0
+        );
+    }
+}
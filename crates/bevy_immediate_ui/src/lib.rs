@@ -1,109 +1,108 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
-use bevy_immediate_core::{capabilities::ImplCapsEmpty, impl_capability_set};
-
-/// Capabilities for bevy_ui.
-pub struct CapsUiBase;
-
-// Definition without bevy_ui_widgets
-impl_capability_set!(
-    CapsUiBase,
-    ImplCapsUiBase > ImplCapsEmpty,
-    (
-        base::CapabilityUiBase,
-        layout_order::CapabilityUiLayoutOrder,
-        look::CapabilityUiLook,
-        disabled::CapabilityUiDisabled,
-        interaction::CapabilityUiInteraction,
-        text::CapabilityUiText,
-        text_input::CapabilityUiTextInput,
-        selected::CapabilityUiSelectable,
-        checked::CapabilityUiChecked,
-        clicked::CapabilityUiClicked,
-        anchored::CapabilityUiAnchored,
+use bevy_immediate_core::{capabilities::ImplCapsEmpty, capability_list, impl_capability_set};
+
+capability_list!(
+    #[macro_export]
+    /// Capabilities of [`CapsUiBase`]
+    ///
+    /// Can be used to define capability set with additional capabilities.
+    /// See [`bevy_immediate_core::impl_capability_set`].
+    caps_ui_base = (
+        crate::base::CapabilityUiBase,
+        crate::layout_order::CapabilityUiLayoutOrder,
+        crate::look::CapabilityUiLook,
+        crate::disabled::CapabilityUiDisabled,
+        crate::interaction::CapabilityUiInteraction,
+        crate::text::CapabilityUiText,
+        crate::text_input::CapabilityUiTextInput,
+        crate::selected::CapabilityUiSelectable,
+        crate::checked::CapabilityUiChecked,
+        crate::clicked::CapabilityUiClicked,
+        crate::anchored::CapabilityUiAnchored,
+        crate::virtual_list::CapabilityUiVirtualList,
+        crate::visibility::CapabilityUiVisibility,
     )
 );
 
-/// Defines capability set for Ui
-///
-/// If bevy_ui_widgets feature is enabled, will include capabilities for it too.
-pub struct CapsUi;
-
 // Definition without bevy_ui_widgets
 #[cfg(not(feature = "bevy_ui_widgets"))]
-impl_capability_set!(
-    CapsUi,
-    ImplCapsUi > ImplCapsUiBase,
-    (
-        base::CapabilityUiBase,
-        layout_order::CapabilityUiLayoutOrder,
-        look::CapabilityUiLook,
-        disabled::CapabilityUiDisabled,
-        interaction::CapabilityUiInteraction,
-        text::CapabilityUiText,
-        text_input::CapabilityUiTextInput,
-        selected::CapabilityUiSelectable,
-        checked::CapabilityUiChecked,
-        clicked::CapabilityUiClicked,
-        anchored::CapabilityUiAnchored,
-    )
+capability_list!(
+    #[macro_export]
+    /// Capabilities of [`CapsUi`]
+    ///
+    /// Can be used to define capability set with additional capabilities.
+    /// See [`bevy_immediate_core::impl_capability_set`].
+    caps_ui = caps_ui_base!
 );
 
 // Definition with bevy_ui_widgets capabilities
+//
+// Listed in full, because capabilities are built in list order
 #[cfg(feature = "bevy_ui_widgets")]
-impl_capability_set!(
-    CapsUi,
-    ImplCapsUi > ImplCapsUiBase,
-    (
-        base::CapabilityUiBase,
-        layout_order::CapabilityUiLayoutOrder,
-        look::CapabilityUiLook,
-        disabled::CapabilityUiDisabled,
-        interaction::CapabilityUiInteraction,
-        text::CapabilityUiText,
-        text_input::CapabilityUiTextInput,
-        selected::CapabilityUiSelectable,
-        checked::CapabilityUiChecked,
-        clicked::CapabilityUiClicked,
-        activated::CapabilityUiActivated,
-        anchored::CapabilityUiAnchored,
+capability_list!(
+    #[macro_export]
+    /// Capabilities of [`CapsUi`]
+    ///
+    /// Can be used to define capability set with additional capabilities.
+    /// See [`bevy_immediate_core::impl_capability_set`].
+    caps_ui = (
+        crate::base::CapabilityUiBase,
+        crate::layout_order::CapabilityUiLayoutOrder,
+        crate::look::CapabilityUiLook,
+        crate::disabled::CapabilityUiDisabled,
+        crate::interaction::CapabilityUiInteraction,
+        crate::text::CapabilityUiText,
+        crate::text_input::CapabilityUiTextInput,
+        crate::selected::CapabilityUiSelectable,
+        crate::checked::CapabilityUiChecked,
+        crate::clicked::CapabilityUiClicked,
+        crate::activated::CapabilityUiActivated,
+        crate::anchored::CapabilityUiAnchored,
+        crate::virtual_list::CapabilityUiVirtualList,
+        crate::visibility::CapabilityUiVisibility,
         // bevy_ui_widgets
-        slider_value::CapabilityUiSliderValue,
+        crate::slider_value::CapabilityUiSliderValue,
     )
 );
 
-/// Defined capability set for Ui with bevy_feathers and bevy_ui_widgets support
-pub struct CapsUiFeathers;
-
 #[cfg(feature = "bevy_feathers")]
-impl_capability_set!(
-    CapsUiFeathers,
-    ImplCapsUiFeathers > ImplCapsUi,
-    (
-        base::CapabilityUiBase,
-        layout_order::CapabilityUiLayoutOrder,
-        look::CapabilityUiLook,
-        disabled::CapabilityUiDisabled,
-        interaction::CapabilityUiInteraction,
-        text::CapabilityUiText,
-        text_input::CapabilityUiTextInput,
-        selected::CapabilityUiSelectable,
-        checked::CapabilityUiChecked,
-        clicked::CapabilityUiClicked,
-        activated::CapabilityUiActivated,
-        anchored::CapabilityUiAnchored,
+capability_list!(
+    #[macro_export]
+    /// Capabilities of [`CapsUiFeathers`]
+    ///
+    /// Can be used to define capability set with additional capabilities.
+    /// See [`bevy_immediate_core::impl_capability_set`].
+    caps_ui_feathers = caps_ui! + (
         // bevy_ui_widgets
-        slider_value::CapabilityUiSliderValue,
-        number_input::CapabilityUiNumberInput,
+        crate::number_input::CapabilityUiNumberInput,
         // bevy_feathers
-        slider_base_color::CapabilityUiSliderBaseColor,
-        button_variant::CapabilityUiFeathersButtonVariant,
-        color_swatch::CapabilityUiColorSwatch,
-        color_plane::CapabilityUiColorPlane,
+        crate::slider_base_color::CapabilityUiSliderBaseColor,
+        crate::button_variant::CapabilityUiFeathersButtonVariant,
+        crate::color_swatch::CapabilityUiColorSwatch,
+        crate::color_plane::CapabilityUiColorPlane,
     )
 );
 
+/// Capabilities for bevy_ui.
+pub struct CapsUiBase;
+
+impl_capability_set!(CapsUiBase, ImplCapsUiBase > ImplCapsEmpty, caps_ui_base!);
+
+/// Defines capability set for Ui
+///
+/// If bevy_ui_widgets feature is enabled, will include capabilities for it too.
+pub struct CapsUi;
+
+impl_capability_set!(CapsUi, ImplCapsUi > ImplCapsUiBase, caps_ui!);
+
+/// Defined capability set for Ui with bevy_feathers and bevy_ui_widgets support
+pub struct CapsUiFeathers;
+
+#[cfg(feature = "bevy_feathers")]
+impl_capability_set!(CapsUiFeathers, ImplCapsUiFeathers > ImplCapsUi, caps_ui_feathers!);
+
 ////////////////////////////////////////////////////////////////////////////////
 
 /// Implements capability that correctly set ups immediate mode systems execution order for UI case
@@ -116,6 +115,9 @@ pub mod layout_order;
 /// Implements functions to access node styling
 pub mod look;
 
+/// Implements hiding of nodes retained by [`bevy_immediate_core::ImmRetention`]
+pub mod retention;
+
 /// Implements functions to manage disabled node state
 pub mod disabled;
 
@@ -172,6 +174,12 @@ pub mod color_plane;
 /// Implements capabilities for floating anchored elements
 pub mod anchored;
 
+/// Implements virtualized lists that build only visible rows
+pub mod virtual_list;
+
+/// Implements functions to hide nodes while keeping their subtree alive
+pub mod visibility;
+
 pub use bevy_immediate_floating_ui::{
     anchored_ui_plugin, floating_ui_focus_plugin, floating_ui_ordering_plugin,
     floating_window_plugin, tooltip_plugin, utils,
@@ -0,0 +1,54 @@
+use bevy_ecs::{
+    component::Component,
+    lifecycle,
+    observer::On,
+    system::{Commands, Query},
+};
+use bevy_ui::{Display, Node};
+
+use bevy_immediate_core::ImmRetained;
+
+/// Hides UI nodes that are retained by [`bevy_immediate_core::ImmRetention`]
+/// with [`Display::None`] and restores original [`Display`] once they are built again.
+pub struct RetainedHiddenPlugin;
+
+impl bevy_app::Plugin for RetainedHiddenPlugin {
+    fn build(&self, app: &mut bevy_app::App) {
+        app.add_observer(on_retained_added);
+        app.add_observer(on_retained_removed);
+    }
+}
+
+/// Stores [`Display`] value of retained node before it was hidden
+#[derive(Component)]
+pub struct RetainedDisplay(pub Display);
+
+fn on_retained_added(
+    trigger: On<lifecycle::Add, ImmRetained>,
+    mut query: Query<&mut Node>,
+    mut commands: Commands,
+) {
+    let entity = trigger.event().entity;
+    let Ok(mut node) = query.get_mut(entity) else {
+        return;
+    };
+
+    commands
+        .entity(entity)
+        .insert(RetainedDisplay(node.display));
+    node.display = Display::None;
+}
+
+fn on_retained_removed(
+    trigger: On<lifecycle::Remove, ImmRetained>,
+    mut query: Query<(&mut Node, &RetainedDisplay)>,
+    mut commands: Commands,
+) {
+    let entity = trigger.event().entity;
+    let Ok((mut node, retained_display)) = query.get_mut(entity) else {
+        return;
+    };
+
+    node.display = retained_display.0;
+    commands.entity(entity).try_remove::<RetainedDisplay>();
+}
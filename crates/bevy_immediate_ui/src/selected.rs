@@ -1,46 +1,22 @@
 use bevy_ecs::system::EntityCommands;
 use bevy_ui::{Selectable, Selected};
 
-use bevy_immediate_core::{CapSet, ImmCapAccessRequests, ImmCapability, ImmEntity, ImplCap};
+use bevy_immediate_core::{imm_capability, imm_capability_ext};
 
 use crate::track_value_change_plugin::{NewValueChange, TrackValueChangePlugin};
 
 /// Implements capability to synchronise [`Selected`] on entities.
+#[imm_capability(
+    write(NewValueChange<bool>),
+    plugins(TrackValueChangePlugin<bool> = TrackValueChangePlugin::<bool>::default())
+)]
 pub struct CapabilityUiSelectable;
 
-impl ImmCapability for CapabilityUiSelectable {
-    fn build<Cap: CapSet>(app: &mut bevy_app::App, cap_req: &mut ImmCapAccessRequests<Cap>) {
-        cap_req.request_component_write::<NewValueChange<bool>>(app.world_mut());
-
-        if !app.is_plugin_added::<TrackValueChangePlugin<bool>>() {
-            app.add_plugins(TrackValueChangePlugin::<bool>::default());
-        }
-    }
-}
-
 /// Synchronises [`bevy_ui::Selected`] on entities.
-pub trait ImmUiSelected {
+#[imm_capability_ext(CapabilityUiSelectable)]
+impl ImmUiSelected {
     /// Control if [`Selected`] is attached using a get/set callback.
-    fn selected_get_set(self, f: impl FnMut(Option<bool>) -> bool) -> Self;
-
-    /// Two-way sync with [`Selected`].
-    fn selected(self, value: &mut bool) -> Self;
-
-    /// Set [`Selected`] without reading widget changes back.
-    fn selected_set(self, value: bool) -> Self;
-
-    /// Sync [`Selected`] when `current == this`.
-    ///
-    /// If the entity becomes selected, `current` is set to `this`.
-    /// If it becomes unselected, `current` is unchanged.
-    fn selected_if_eq<T: PartialEq>(self, this: T, current: &mut T) -> Self;
-}
-
-impl<Cap> ImmUiSelected for ImmEntity<'_, '_, '_, Cap>
-where
-    Cap: ImplCap<CapabilityUiSelectable>,
-{
-    fn selected_get_set(mut self, mut f: impl FnMut(Option<bool>) -> bool) -> Self {
+    pub fn selected_get_set(mut self, mut f: impl FnMut(Option<bool>) -> bool) -> Self {
         let current = f(None);
         let mut new = current;
         self = self.selected(&mut new);
@@ -50,7 +26,8 @@ where
         self
     }
 
-    fn selected_set(mut self, value: bool) -> Self {
+    /// Set [`Selected`] without reading widget changes back.
+    pub fn selected_set(mut self, value: bool) -> Self {
         'initialized: {
             let Ok(entity) = self.cap_get_entity() else {
                 break 'initialized;
@@ -76,7 +53,8 @@ where
         self
     }
 
-    fn selected(mut self, value: &mut bool) -> Self {
+    /// Two-way sync with [`Selected`].
+    pub fn selected(mut self, value: &mut bool) -> Self {
         'initialized: {
             let Ok(mut entity) = self.cap_get_entity_mut() else {
                 break 'initialized;
@@ -116,7 +94,11 @@ where
         self
     }
 
-    fn selected_if_eq<T: PartialEq>(mut self, this: T, current: &mut T) -> Self {
+    /// Sync [`Selected`] when `current == this`.
+    ///
+    /// If the entity becomes selected, `current` is set to `this`.
+    /// If it becomes unselected, `current` is unchanged.
+    pub fn selected_if_eq<T: PartialEq>(mut self, this: T, current: &mut T) -> Self {
         let before = current == &this;
         let mut after = before;
 
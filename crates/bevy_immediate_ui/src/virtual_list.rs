@@ -0,0 +1,149 @@
+use bevy_ecs::world::EntityWorldMut;
+use bevy_ui::{ComputedNode, Node, ScrollPosition, Val};
+
+use crate::layout_order::CapabilityUiLayoutOrder;
+use bevy_immediate_core::{CapSet, Imm, ImmCapAccessRequests, ImmCapability, ImmEntity, ImplCap};
+
+/// Implements capability to build only visible part of very long lists
+pub struct CapabilityUiVirtualList;
+
+impl ImmCapability for CapabilityUiVirtualList {
+    fn build<Cap: CapSet>(app: &mut bevy_app::App, cap_req: &mut ImmCapAccessRequests<Cap>) {
+        cap_req.request_component_read::<ScrollPosition>(app.world_mut());
+        cap_req.request_component_read::<ComputedNode>(app.world_mut());
+        cap_req.request_component_write::<Node>(app.world_mut());
+    }
+}
+
+/// Amount of rows built outside of visible area on each side by [`ImmUiVirtualList::virtual_list`]
+pub const VIRTUAL_LIST_OVERSCAN: usize = 2;
+
+/// Viewport height in logical pixels used by [`ImmUiVirtualList::virtual_list`]
+/// before container layout is computed
+pub const VIRTUAL_LIST_FALLBACK_VIEWPORT: f32 = 1080.;
+
+/// Implements virtualized lists
+pub trait ImmUiVirtualList<'w, 's, Caps: CapSet> {
+    /// Build only rows that are visible in this scrollable container.
+    ///
+    /// Container should scroll vertically (`Overflow::scroll_y()`) and every row
+    /// must be `item_extent` logical pixels high. Closure is called for each visible row index.
+    /// Spacer nodes are inserted before and after visible rows to keep scrollbars correct.
+    ///
+    /// Until container layout is computed, [`VIRTUAL_LIST_FALLBACK_VIEWPORT`] is used
+    /// as viewport height.
+    fn virtual_list(
+        self,
+        item_count: usize,
+        item_extent: f32,
+        f: impl FnMut(&mut Imm<'w, 's, Caps>, usize),
+    ) -> Self;
+
+    /// Same as [`Self::virtual_list`] with custom amount of rows built outside of visible area
+    fn virtual_list_with_overscan(
+        self,
+        item_count: usize,
+        item_extent: f32,
+        overscan: usize,
+        f: impl FnMut(&mut Imm<'w, 's, Caps>, usize),
+    ) -> Self;
+}
+
+impl<'w, 's, Caps> ImmUiVirtualList<'w, 's, Caps> for ImmEntity<'_, 'w, 's, Caps>
+where
+    Caps: ImplCap<CapabilityUiVirtualList> + ImplCap<CapabilityUiLayoutOrder>,
+{
+    fn virtual_list(
+        self,
+        item_count: usize,
+        item_extent: f32,
+        f: impl FnMut(&mut Imm<'w, 's, Caps>, usize),
+    ) -> Self {
+        self.virtual_list_with_overscan(item_count, item_extent, VIRTUAL_LIST_OVERSCAN, f)
+    }
+
+    fn virtual_list_with_overscan(
+        self,
+        item_count: usize,
+        item_extent: f32,
+        overscan: usize,
+        mut f: impl FnMut(&mut Imm<'w, 's, Caps>, usize),
+    ) -> Self {
+        let scroll = self
+            .cap_get_component::<ScrollPosition>()
+            .ok()
+            .flatten()
+            .map(|scroll| scroll.y)
+            .unwrap_or(0.);
+        let viewport = self
+            .cap_get_component::<ComputedNode>()
+            .ok()
+            .flatten()
+            .map(|node| node.size().y * node.inverse_scale_factor())
+            .filter(|&height| height > 0.)
+            .unwrap_or(VIRTUAL_LIST_FALLBACK_VIEWPORT);
+
+        let range = visible_range(item_count, item_extent, scroll, viewport, overscan);
+
+        self.add(|ui| {
+            spacer(ui, "virtual_list_before", range.start as f32 * item_extent);
+
+            for index in range.clone() {
+                let mut ui = ui.with_add_id_pref(index);
+                f(&mut ui, index);
+            }
+
+            spacer(
+                ui,
+                "virtual_list_after",
+                (item_count - range.end) as f32 * item_extent,
+            );
+        })
+    }
+}
+
+fn visible_range(
+    item_count: usize,
+    item_extent: f32,
+    scroll: f32,
+    viewport: f32,
+    overscan: usize,
+) -> std::ops::Range<usize> {
+    if item_extent <= 0. {
+        return 0..item_count;
+    }
+
+    let first = (scroll.max(0.) / item_extent).floor() as usize;
+    let last = ((scroll.max(0.) + viewport.max(0.)) / item_extent).ceil() as usize;
+
+    let start = first.saturating_sub(overscan).min(item_count);
+    let end = last.saturating_add(overscan).min(item_count).max(start);
+    start..end
+}
+
+fn spacer<Caps>(ui: &mut Imm<'_, '_, Caps>, id: &str, height: f32)
+where
+    Caps: ImplCap<CapabilityUiVirtualList>,
+{
+    let height = Val::Px(height);
+
+    let mut entity = ui.ch_id(id).on_spawn_insert(|| Node {
+        flex_shrink: 0.,
+        ..Default::default()
+    });
+    if let Ok(Some(mut node)) = entity.cap_get_component_mut::<Node>() {
+        if node.height != height {
+            node.height = height;
+        }
+        return;
+    }
+
+    // Node is not accessible until entity is spawned
+    entity
+        .entity_commands()
+        .queue(move |mut entity: EntityWorldMut| {
+            if let Some(mut node) = entity.get_mut::<Node>() {
+                node.height = height;
+            }
+        });
+}
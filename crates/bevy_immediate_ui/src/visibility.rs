@@ -0,0 +1,114 @@
+use bevy_camera::visibility::Visibility;
+use bevy_ecs::{component::Component, world::EntityWorldMut};
+use bevy_ui::{Display, Node};
+
+use bevy_immediate_core::{
+    CapSet, Imm, ImmCapAccessRequests, ImmCapability, ImmEntity, ImmId, ImplCap,
+};
+
+/// Implements capability to hide nodes without despawning their subtree
+pub struct CapabilityUiVisibility;
+
+impl ImmCapability for CapabilityUiVisibility {
+    fn build<Cap: CapSet>(app: &mut bevy_app::App, cap_req: &mut ImmCapAccessRequests<Cap>) {
+        let _ = cap_req;
+        let _ = app;
+    }
+}
+
+/// Stores [`Display`] value of node before it was hidden with [`ImmUiVisibility::displayed`]
+#[derive(Component)]
+pub struct HiddenDisplay(pub Display);
+
+/// Implements functions to hide nodes while keeping their entities alive
+pub trait ImmUiVisibility<'w, 's, Caps: CapSet> {
+    /// Set node visible or hidden with [`Visibility`]
+    ///
+    /// Hidden node still takes up space in layout.
+    fn visible(self, visible: bool) -> Self;
+
+    /// Set node displayed or removed from layout with [`Display::None`]
+    ///
+    /// Previous [`Display`] value is restored once node is displayed again.
+    /// Call it after [`Node`] is inserted.
+    fn displayed(self, displayed: bool) -> Self;
+
+    /// Same as [`Self::visible`], but builds children only while node is visible
+    ///
+    /// While hidden, closure is skipped and previously built children are kept alive
+    /// with their state. Children are built once when entity is spawned hidden.
+    fn add_visible(self, visible: bool, f: impl FnOnce(&mut Imm<'w, 's, Caps>)) -> Self;
+
+    /// Same as [`Self::displayed`], but builds children only while node is displayed
+    ///
+    /// While hidden, closure is skipped and previously built children are kept alive
+    /// with their state. Children are built once when entity is spawned hidden.
+    fn add_displayed(self, displayed: bool, f: impl FnOnce(&mut Imm<'w, 's, Caps>)) -> Self;
+}
+
+impl<'w, 's, Caps> ImmUiVisibility<'w, 's, Caps> for ImmEntity<'_, 'w, 's, Caps>
+where
+    Caps: ImplCap<CapabilityUiVisibility>,
+{
+    fn visible(mut self, visible: bool) -> Self {
+        struct VisibleMarker;
+        if self.hash_set_typ::<VisibleMarker>(ImmId::new(visible)) {
+            let visibility = if visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+            self.entity_commands().insert(visibility);
+        }
+        self
+    }
+
+    fn displayed(mut self, displayed: bool) -> Self {
+        struct DisplayedMarker;
+        if !self.hash_set_typ::<DisplayedMarker>(ImmId::new(displayed)) {
+            return self;
+        }
+
+        if displayed {
+            self.entity_commands().queue(|mut entity: EntityWorldMut| {
+                let Some(HiddenDisplay(display)) = entity.take::<HiddenDisplay>() else {
+                    return;
+                };
+                if let Some(mut node) = entity.get_mut::<Node>() {
+                    node.display = display;
+                }
+            });
+        } else {
+            self.entity_commands().queue(|mut entity: EntityWorldMut| {
+                if entity.contains::<HiddenDisplay>() {
+                    return;
+                }
+                let Some(mut node) = entity.get_mut::<Node>() else {
+                    return;
+                };
+                let display = node.display;
+                node.display = Display::None;
+                entity.insert(HiddenDisplay(display));
+            });
+        }
+        self
+    }
+
+    fn add_visible(self, visible: bool, f: impl FnOnce(&mut Imm<'w, 's, Caps>)) -> Self {
+        let entity = self.visible(visible);
+        if visible || entity.will_be_spawned() {
+            entity.add(f)
+        } else {
+            entity.keep_children_alive()
+        }
+    }
+
+    fn add_displayed(self, displayed: bool, f: impl FnOnce(&mut Imm<'w, 's, Caps>)) -> Self {
+        let entity = self.displayed(displayed);
+        if displayed || entity.will_be_spawned() {
+            entity.add(f)
+        } else {
+            entity.keep_children_alive()
+        }
+    }
+}
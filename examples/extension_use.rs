@@ -14,30 +14,18 @@ pub struct CapsMyUi;
 impl_capability_set!(
     CapsMyUi,
     ImplCapMyUi > ImplCapsUi,
-    (
-        // You need to list all capabilities
-        //
-        // Macro will add compile time check to check that
-        // you correctly listed them
-        bevy_immediate::ui::base::CapabilityUiBase,
-        bevy_immediate::ui::layout_order::CapabilityUiLayoutOrder,
-        bevy_immediate::ui::look::CapabilityUiLook,
-        bevy_immediate::ui::disabled::CapabilityUiDisabled,
-        bevy_immediate::ui::interaction::CapabilityUiInteraction,
-        bevy_immediate::ui::text::CapabilityUiText,
-        bevy_immediate::ui::text_input::CapabilityUiTextInput,
-        bevy_immediate::ui::selected::CapabilityUiSelectable,
-        bevy_immediate::ui::checked::CapabilityUiChecked,
-        bevy_immediate::ui::clicked::CapabilityUiClicked,
-        bevy_immediate::ui::activated::CapabilityUiActivated,
-        bevy_immediate::ui::anchored::CapabilityUiAnchored,
-        // bevy_ui_widgets
-        bevy_immediate_ui::slider_value::CapabilityUiSliderValue,
-        bevy_immediate_ui::number_input::CapabilityUiNumberInput,
-        //
-        // Add your own capabilities
-        crate::extension::CapUiToggle,
-    )
+    // Start from capabilities of `CapsUi`
+    //
+    // Macro will add compile time check to check that
+    // all capabilities of `ImplCapsUi` are included
+    bevy_immediate::ui::caps_ui!
+        + (
+            // bevy_ui_widgets
+            bevy_immediate_ui::number_input::CapabilityUiNumberInput,
+            //
+            // Add your own capabilities
+            crate::extension::CapUiToggle,
+        )
 );
 
 pub struct ExtensionUseExamplePlugin;
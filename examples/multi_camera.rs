@@ -0,0 +1,192 @@
+//! Window is split between two cameras. Each camera renders its own immediate mode UI.
+//!
+//! Dropdowns are spawned without parent entity. They are rendered by the camera
+//! of the button they are anchored to, unless `UiTargetCamera` is set explicitly.
+
+use bevy::camera::{ClearColorConfig, Viewport};
+use bevy::prelude::*;
+use bevy::ui_widgets::Button;
+use bevy::window::PrimaryWindow;
+use bevy_immediate::{
+    BevyImmediatePlugin, Imm, ImmCtx,
+    attach::{BevyImmediateAttachPlugin, ImmediateAttach},
+    ui::{
+        CapsUi, activated::ImmUiActivated, anchored::ImmUiAnchored,
+        floating_ui_focus_plugin::FocusCloseCurrentTree, text::ImmUiText,
+    },
+    utils::ImmLocalHashMemoryHelper,
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        // UI attached to panel entities
+        .add_plugins(BevyImmediateAttachPlugin::<CapsUi, CameraPanelRoot>::new())
+        // UI built from system for specific camera
+        .add_plugins(BevyImmediatePlugin::<CapsUi>::new())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (split_viewports, header_ui_system))
+        .run();
+}
+
+/// Half of window rendered by camera
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash)]
+enum Side {
+    Left,
+    Right,
+}
+
+fn setup(mut commands: Commands) {
+    for (side, order, color) in [
+        (Side::Left, 0, Color::srgb(0.08, 0.08, 0.12)),
+        (Side::Right, 1, Color::srgb(0.12, 0.08, 0.08)),
+    ] {
+        let camera = commands
+            .spawn((
+                Camera2d,
+                Camera {
+                    order,
+                    clear_color: ClearColorConfig::Custom(color),
+                    ..default()
+                },
+                side,
+            ))
+            .id();
+
+        // Panel and all its children are rendered by this camera
+        commands.spawn((
+            Node {
+                width: percent(100.),
+                height: percent(100.),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: px(10.),
+                ..default()
+            },
+            UiTargetCamera(camera),
+            CameraPanelRoot,
+        ));
+    }
+}
+
+/// Each camera renders half of window
+fn split_viewports(
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&mut Camera, &Side)>,
+) {
+    let Ok(window) = window.single() else {
+        return;
+    };
+    let size = window.physical_size();
+    let half = UVec2::new((size.x / 2).max(1), size.y.max(1));
+
+    for (mut camera, side) in cameras.iter_mut() {
+        let position = match side {
+            Side::Left => UVec2::ZERO,
+            Side::Right => UVec2::new(half.x, 0),
+        };
+        let changed = camera.viewport.as_ref().is_none_or(|viewport| {
+            viewport.physical_position != position || viewport.physical_size != half
+        });
+        if changed {
+            camera.viewport = Some(Viewport {
+                physical_position: position,
+                physical_size: half,
+                ..default()
+            });
+        }
+    }
+}
+
+/// Header is built for right camera with `build_immediate_root_for`
+fn header_ui_system(ctx: ImmCtx<CapsUi>, cameras: Query<(Entity, &Side)>) {
+    let Some((camera, _)) = cameras.iter().find(|(_, side)| **side == Side::Right) else {
+        return;
+    };
+
+    ctx.build_immediate_root_for("header", camera)
+        .ch()
+        .on_spawn_insert(|| Node {
+            position_type: PositionType::Absolute,
+            top: px(10.),
+            left: px(10.),
+            ..default()
+        })
+        .add(|ui| {
+            ui.ch()
+                .on_spawn_text("Header built for right camera with build_immediate_root_for");
+        });
+}
+
+#[derive(Component)]
+struct CameraPanelRoot;
+
+impl ImmediateAttach<CapsUi> for CameraPanelRoot {
+    type Params = ();
+
+    fn construct(ui: &mut Imm<CapsUi>, _: &mut ()) {
+        ui.ch().on_spawn_text("Panel rendered by its own camera");
+
+        for idx in 0..3 {
+            let mut button = ui
+                .ch_id(("button", idx))
+                .on_spawn_insert(|| {
+                    (
+                        Button,
+                        Node {
+                            padding: UiRect::all(px(5.)),
+                            border: UiRect::all(px(1.)),
+                            ..default()
+                        },
+                        BorderColor::all(Color::WHITE),
+                    )
+                })
+                .add(|ui| {
+                    ui.ch().on_spawn_text_fn(|| format!("Open dropdown {idx}"));
+                });
+
+            let mut open = ImmLocalHashMemoryHelper::new(&mut button, "open", &false);
+            if button.activated() {
+                open.store(&true);
+            }
+
+            if open.is_stored(&true) {
+                // Dropdown has no parent. Its camera is taken from anchor target (this button)
+                button = button.add_dropdown(
+                    || {
+                        open.store(&false);
+                    },
+                    |ui| {
+                        ui.ch()
+                            .on_spawn_insert(|| {
+                                (
+                                    Node {
+                                        flex_direction: FlexDirection::Column,
+                                        padding: UiRect::all(px(5.)),
+                                        ..default()
+                                    },
+                                    BackgroundColor(Color::BLACK),
+                                )
+                            })
+                            .add(|ui| {
+                                for item in 0..3 {
+                                    let mut entry = ui
+                                        .ch_id(item)
+                                        .on_spawn_insert(|| (Button, Node::default()))
+                                        .add(|ui| {
+                                            ui.ch().on_spawn_text_fn(|| format!("Item {item}"));
+                                        });
+                                    if entry.activated() {
+                                        entry.entity_commands().trigger(FocusCloseCurrentTree::new);
+                                    }
+                                }
+                            });
+                    },
+                );
+            }
+
+            open.finalize(&mut button);
+        }
+    }
+}
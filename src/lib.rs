@@ -10,8 +10,9 @@ pub use bevy_immediate_core::capabilities;
 pub use bevy_immediate_core::impl_capability_set;
 pub use bevy_immediate_core::{
     CapSet, ImmCapAccessRequests, ImmCapAccessRequestsResource, ImmCapQueryParam, ImmCapability,
-    ImplCap, ImplCapsEmpty,
+    ImmCapabilityRequires, ImplCap, ImplCapAll, ImplCapsEmpty,
 };
+pub use bevy_immediate_core::{capability_list, imm_capability, imm_capability_ext};
 
 /// Reexport helper macros
 pub use bevy_immediate_core::{lch, lid};